@@ -4,10 +4,12 @@
 
 use anyhow::{Context, Result};
 use chrono::NaiveDate;
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 use liw_core::{
-    install_schedule, render_grid, renderer::save_grid, scheduler::is_schedule_installed,
-    set_wallpaper, uninstall_schedule, Config, Mode, WeekGrid,
+    due_date_from_conception, estimate_lifespan, install_schedule, journal::parse_week,
+    render_grid_with, renderer::save_grid, scheduler::is_schedule_installed, set_wallpaper,
+    uninstall_schedule, Config, CountdownTarget, Granularity, Journal, LifeEvent, Mode, Person,
+    WeekGrid,
 };
 use std::path::PathBuf;
 
@@ -26,53 +28,186 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// Generate and optionally set wallpaper
-    Generate {
-        /// Mode: life, year-end, or next-months (defaults to saved config)
-        #[arg(short, long)]
-        mode: Option<String>,
+    Generate(Box<GenerateArgs>),
 
-        /// Date of birth (YYYY-MM-DD) for life mode
-        #[arg(long)]
-        dob: Option<String>,
+    /// Manage configuration
+    #[command(subcommand)]
+    Config(ConfigCommands),
 
-        /// Expected lifespan in years (default: 80)
-        #[arg(long)]
-        lifespan: Option<u8>,
+    /// Manage life events highlighted in the grid
+    #[command(subcommand)]
+    Event(EventCommands),
 
-        /// Number of months for next-months mode (default: 6)
-        #[arg(long)]
-        months: Option<u8>,
+    /// Manage countdown targets
+    #[command(subcommand)]
+    Target(TargetCommands),
 
-        /// Just preview, don't set as wallpaper
-        #[arg(short, long)]
-        preview: bool,
+    /// Manage people shown in family mode
+    #[command(subcommand)]
+    Person(PersonCommands),
 
-        /// Output file path (default: auto-generated)
-        #[arg(short, long)]
-        output: Option<PathBuf>,
+    /// Keep weekly notes and ratings
+    #[command(subcommand)]
+    Journal(JournalCommands),
 
-        /// Screen width
+    /// Estimate lifespan from an embedded period life table
+    EstimateLifespan {
+        /// Sex: male or female, defaults to the saved one
         #[arg(long)]
-        width: Option<u32>,
-
-        /// Screen height
+        sex: Option<String>,
+        /// Country code or name (e.g. US, GB, Japan), defaults to the saved one
         #[arg(long)]
-        height: Option<u32>,
-
-        /// Theme: minimal, terminal, dark, sunset
-        #[arg(short, long)]
-        theme: Option<String>,
+        country: Option<String>,
+        /// Date of birth (YYYY-MM-DD), defaults to the saved one
+        #[arg(long)]
+        dob: Option<String>,
+        /// Save the estimate as lifespan_years in the config
+        #[arg(long)]
+        save: bool,
     },
 
-    /// Manage configuration
-    #[command(subcommand)]
-    Config(ConfigCommands),
-
-    /// Manage weekly schedule
+    /// Manage daily schedule
     #[command(subcommand)]
     Schedule(ScheduleCommands),
 }
 
+#[derive(Args)]
+struct GenerateArgs {
+    /// Mode: life, year-end, full-year, next-months, range, countdown, family, tail-end,
+    /// retirement, term, fiscal, sprint, or pregnancy (defaults to saved config)
+    #[arg(short, long)]
+    mode: Option<String>,
+
+    /// Date of birth (YYYY-MM-DD) for life mode
+    #[arg(long)]
+    dob: Option<String>,
+
+    /// Expected lifespan in years (default: 80)
+    #[arg(long)]
+    lifespan: Option<u8>,
+
+    /// Start each life mode row on a birthday, so rows line up with ages
+    #[arg(long)]
+    age_rows: bool,
+
+    /// Number of months for next-months mode (default: 6)
+    #[arg(long)]
+    months: Option<u8>,
+
+    /// First day (YYYY-MM-DD) for range mode
+    #[arg(long)]
+    start: Option<String>,
+
+    /// Last day (YYYY-MM-DD) for range mode
+    #[arg(long)]
+    end: Option<String>,
+
+    /// Custom title for range mode
+    #[arg(long)]
+    title: Option<String>,
+
+    /// Lay out full-year mode with one row per month
+    #[arg(long)]
+    month_rows: bool,
+
+    /// Age at retirement for retirement mode (default: 65)
+    #[arg(long)]
+    retirement_age: Option<u8>,
+
+    /// Retirement date (YYYY-MM-DD) for retirement mode, instead of an age
+    #[arg(long)]
+    retire_on: Option<String>,
+
+    /// Vacation weeks per year, subtracted from working weeks
+    #[arg(long)]
+    vacation_weeks: Option<u8>,
+
+    /// Public holidays (days) per year, subtracted from working weeks
+    #[arg(long)]
+    holidays: Option<u8>,
+
+    /// First month of the fiscal year (1-12 or name) for fiscal mode
+    #[arg(long)]
+    fiscal_start: Option<String>,
+
+    /// First day of sprint 1 (YYYY-MM-DD) for sprint mode
+    #[arg(long)]
+    anchor: Option<String>,
+
+    /// Release date (YYYY-MM-DD) for sprint mode
+    #[arg(long)]
+    release: Option<String>,
+
+    /// Weeks per sprint for sprint mode (default: 2)
+    #[arg(long)]
+    sprint_weeks: Option<u8>,
+
+    /// Due date (YYYY-MM-DD) for pregnancy mode
+    #[arg(long)]
+    due: Option<String>,
+
+    /// Conception date (YYYY-MM-DD) for pregnancy mode, used to estimate the due date
+    #[arg(long, conflicts_with = "due")]
+    conceived: Option<String>,
+
+    /// Birth date (YYYY-MM-DD) for pregnancy mode, if different from the due date
+    #[arg(long)]
+    born: Option<String>,
+
+    /// Shade future weeks in life mode by the probability of being alive
+    #[arg(long)]
+    survival: bool,
+
+    /// Heatmap for past weeks: a CSV or JSON file of dated values, or
+    /// git:/path/to/repo[,/another/repo] to count commits
+    #[arg(long)]
+    data: Option<String>,
+
+    /// Author email whose commits a git data source counts (default: git's user.email)
+    #[arg(long)]
+    author: Option<String>,
+
+    /// iCalendar file whose events mark weeks, by category (repeatable)
+    #[arg(long, value_name = "FILE")]
+    ics: Vec<PathBuf>,
+
+    /// Time unit of each cell: day, week, month, year (default: week)
+    #[arg(long, visible_alias = "unit")]
+    granularity: Option<String>,
+
+    /// Label rows with their year or age and number the columns
+    #[arg(long)]
+    labels: bool,
+
+    /// Fill the current week by days elapsed: solid, horizontal, radial
+    #[arg(long)]
+    fill: Option<String>,
+
+    /// Render the grid as of this date (YYYY-MM-DD) instead of today
+    #[arg(long)]
+    as_of: Option<String>,
+
+    /// Just preview, don't set as wallpaper
+    #[arg(short, long)]
+    preview: bool,
+
+    /// Output file path (default: auto-generated)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Screen width
+    #[arg(long)]
+    width: Option<u32>,
+
+    /// Screen height
+    #[arg(long)]
+    height: Option<u32>,
+
+    /// Theme: minimal, terminal, dark, sunset
+    #[arg(short, long)]
+    theme: Option<String>,
+}
+
 #[derive(Subcommand)]
 enum ConfigCommands {
     /// Show current configuration
@@ -80,7 +215,12 @@ enum ConfigCommands {
 
     /// Set a configuration value
     Set {
-        /// Config key (dob, lifespan, theme, width, height, default_mode, next_months)
+        /// Config key (dob, lifespan, sex, country, survival_shading, survival_csv, data_source,
+        /// git_author, ics_files, category_color, theme, width, height, default_mode, next_months,
+        /// granularity, week_start, week_numbering, range_start, range_end, range_title,
+        /// month_rows, age_rows, row_labels, row_label_every, column_labels, column_label_every,
+        /// current_week_fill, retirement_age, retirement_date, vacation_weeks, public_holidays,
+        /// fiscal_start_month, sprint_anchor, release_date, sprint_weeks, due_date, birth_date)
         key: String,
         /// Value to set
         value: String,
@@ -93,12 +233,106 @@ enum ConfigCommands {
     Path,
 }
 
+#[derive(Subcommand)]
+enum EventCommands {
+    /// Add a life event
+    Add {
+        /// Event name (e.g. "Graduation")
+        name: String,
+        /// Event date (YYYY-MM-DD)
+        date: String,
+        /// Hex color for the event cell (e.g. "#FFD700")
+        #[arg(short, long)]
+        color: Option<String>,
+    },
+
+    /// List life events
+    List,
+
+    /// Remove a life event by name
+    Remove {
+        /// Event name
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum TargetCommands {
+    /// Add a countdown target
+    Add {
+        /// Target name (e.g. "Release 2.0")
+        name: String,
+        /// Target date (YYYY-MM-DD)
+        date: String,
+        /// Hex color for the target marker (e.g. "#FF5500")
+        #[arg(short, long)]
+        color: Option<String>,
+    },
+
+    /// List countdown targets
+    List,
+
+    /// Remove a countdown target by name
+    Remove {
+        /// Target name
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum PersonCommands {
+    /// Add a person to family mode
+    Add {
+        /// Person's name (e.g. "Alex")
+        name: String,
+        /// Date of birth (YYYY-MM-DD)
+        dob: String,
+        /// Expected lifespan in years (default: the configured lifespan)
+        #[arg(short, long)]
+        lifespan: Option<u8>,
+        /// Hex color for the person's cells (e.g. "#4A90D9")
+        #[arg(short, long)]
+        color: Option<String>,
+    },
+
+    /// List people in family mode
+    List,
+
+    /// Remove a person by name
+    Remove {
+        /// Person's name
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum JournalCommands {
+    /// Add a note to a week
+    Add {
+        /// What happened (e.g. "shipped v1")
+        note: String,
+        /// How the week went, from 1 (bad) to 5 (great)
+        #[arg(short, long)]
+        rating: Option<u8>,
+        /// Week as YYYY-Www or any date in it (default: this week)
+        #[arg(short, long)]
+        week: Option<String>,
+    },
+
+    /// Show entries for one week, or all of them
+    Show {
+        /// Week as YYYY-Www or any date in it
+        #[arg(short, long)]
+        week: Option<String>,
+    },
+}
+
 #[derive(Subcommand)]
 enum ScheduleCommands {
-    /// Install weekly schedule
+    /// Install daily schedule
     Install,
 
-    /// Uninstall weekly schedule
+    /// Uninstall daily schedule
     Uninstall,
 
     /// Check schedule status
@@ -109,25 +343,45 @@ fn main() -> Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Generate {
-            mode,
-            dob,
-            lifespan,
-            months,
-            preview,
-            output,
-            width,
-            height,
-            theme,
-        } => cmd_generate(
-            mode, dob, lifespan, months, preview, output, width, height, theme,
-        ),
+        Commands::Generate(args) => cmd_generate(*args),
         Commands::Config(cmd) => match cmd {
             ConfigCommands::Show => cmd_config_show(),
             ConfigCommands::Set { key, value } => cmd_config_set(&key, &value),
             ConfigCommands::Reset => cmd_config_reset(),
             ConfigCommands::Path => cmd_config_path(),
         },
+        Commands::Event(cmd) => match cmd {
+            EventCommands::Add { name, date, color } => cmd_event_add(name, &date, color),
+            EventCommands::List => cmd_event_list(),
+            EventCommands::Remove { name } => cmd_event_remove(&name),
+        },
+        Commands::Target(cmd) => match cmd {
+            TargetCommands::Add { name, date, color } => cmd_target_add(name, &date, color),
+            TargetCommands::List => cmd_target_list(),
+            TargetCommands::Remove { name } => cmd_target_remove(&name),
+        },
+        Commands::Person(cmd) => match cmd {
+            PersonCommands::Add {
+                name,
+                dob,
+                lifespan,
+                color,
+            } => cmd_person_add(name, &dob, lifespan, color),
+            PersonCommands::List => cmd_person_list(),
+            PersonCommands::Remove { name } => cmd_person_remove(&name),
+        },
+        Commands::Journal(cmd) => match cmd {
+            JournalCommands::Add { note, rating, week } => {
+                cmd_journal_add(&note, rating, week.as_deref())
+            }
+            JournalCommands::Show { week } => cmd_journal_show(week.as_deref()),
+        },
+        Commands::EstimateLifespan {
+            sex,
+            country,
+            dob,
+            save,
+        } => cmd_estimate_lifespan(sex.as_deref(), country.as_deref(), dob.as_deref(), save),
         Commands::Schedule(cmd) => match cmd {
             ScheduleCommands::Install => cmd_schedule_install(),
             ScheduleCommands::Uninstall => cmd_schedule_uninstall(),
@@ -136,71 +390,145 @@ fn main() -> Result<()> {
     }
 }
 
-#[allow(clippy::too_many_arguments)]
-fn cmd_generate(
-    mode_str: Option<String>,
-    dob_str: Option<String>,
-    lifespan: Option<u8>,
-    months: Option<u8>,
-    preview: bool,
-    output: Option<PathBuf>,
-    width: Option<u32>,
-    height: Option<u32>,
-    theme_str: Option<String>,
-) -> Result<()> {
+fn cmd_generate(args: GenerateArgs) -> Result<()> {
     // Load config for defaults
     let mut config = Config::load().unwrap_or_default();
     let mut config_changed = false;
 
     // Apply overrides from CLI
-    if let Some(ref t) = theme_str {
+    if let Some(ref t) = args.theme {
         config.set("theme", t)?;
         config_changed = true;
     }
-    if let Some(w) = width {
+    if let Some(w) = args.width {
         config.screen_width = w;
         config_changed = true;
     }
-    if let Some(h) = height {
+    if let Some(h) = args.height {
         config.screen_height = h;
         config_changed = true;
     }
-    if let Some(l) = lifespan {
+    if let Some(ref data) = args.data {
+        config.set("data_source", data)?;
+        config_changed = true;
+    }
+    if let Some(ref author) = args.author {
+        config.set("git_author", author)?;
+        config_changed = true;
+    }
+    if !args.ics.is_empty() {
+        config.ics_files = args.ics.clone();
+        config_changed = true;
+    }
+    if let Some(ref g) = args.granularity {
+        config.set("granularity", g)?;
+        config_changed = true;
+    }
+    if let Some(l) = args.lifespan {
         config.lifespan_years = l;
         config_changed = true;
     }
-    if let Some(m) = months {
+    if let Some(m) = args.months {
         config.next_months = m;
         config_changed = true;
     }
-
-    // Parse DOB
-    let dob = if let Some(ref dob_str) = dob_str {
-        let parsed = Some(
-            NaiveDate::parse_from_str(dob_str, "%Y-%m-%d")
-                .with_context(|| format!("Invalid date format: {}. Use YYYY-MM-DD", dob_str))?,
-        );
-        config.dob = parsed;
+    if let Some(ref dob) = args.dob {
+        config.dob = Some(parse_date(dob)?);
         config_changed = true;
-        parsed
-    } else {
-        config.dob
-    };
+    }
+    if let Some(ref start) = args.start {
+        config.range_start = Some(parse_date(start)?);
+        config_changed = true;
+    }
+    if let Some(ref end) = args.end {
+        config.range_end = Some(parse_date(end)?);
+        config_changed = true;
+    }
+    if let Some(title) = args.title {
+        config.range_title = Some(title);
+        config_changed = true;
+    }
+    if args.month_rows && !config.month_rows {
+        config.month_rows = true;
+        config_changed = true;
+    }
+    if args.age_rows && !config.age_rows {
+        config.age_rows = true;
+        config_changed = true;
+    }
+    if let Some(age) = args.retirement_age {
+        config.retirement_age = age;
+        config_changed = true;
+    }
+    if let Some(ref date) = args.retire_on {
+        config.retirement_date = Some(parse_date(date)?);
+        config_changed = true;
+    }
+    if let Some(weeks) = args.vacation_weeks {
+        config.set("vacation_weeks", &weeks.to_string())?;
+        config_changed = true;
+    }
+    if let Some(days) = args.holidays {
+        config.public_holidays = days;
+        config_changed = true;
+    }
+    if let Some(ref month) = args.fiscal_start {
+        config.set("fiscal_start_month", month)?;
+        config_changed = true;
+    }
+    if let Some(ref anchor) = args.anchor {
+        config.sprint_anchor = Some(parse_date(anchor)?);
+        config_changed = true;
+    }
+    if let Some(ref release) = args.release {
+        config.release_date = Some(parse_date(release)?);
+        config_changed = true;
+    }
+    if let Some(weeks) = args.sprint_weeks {
+        config.set("sprint_weeks", &weeks.to_string())?;
+        config_changed = true;
+    }
+    if let Some(ref due) = args.due {
+        config.due_date = Some(parse_date(due)?);
+        config_changed = true;
+    }
+    if let Some(ref conceived) = args.conceived {
+        config.due_date = Some(due_date_from_conception(parse_date(conceived)?));
+        config_changed = true;
+    }
+    if let Some(ref born) = args.born {
+        config.birth_date = Some(parse_date(born)?);
+        config_changed = true;
+    }
+    if args.labels && !(config.row_labels && config.column_labels) {
+        config.row_labels = true;
+        config.column_labels = true;
+        config_changed = true;
+    }
+    if let Some(ref fill) = args.fill {
+        config.set("current_week_fill", fill)?;
+        config_changed = true;
+    }
+    if args.survival && !config.survival_shading {
+        config.survival_shading = true;
+        config_changed = true;
+    }
 
     // Parse mode
-    let mode_override = mode_str.is_some();
-    let mode_str = mode_str.unwrap_or_else(|| config.default_mode.clone());
+    let mode_override = args.mode.is_some();
+    let mode_str = args.mode.unwrap_or_else(|| config.default_mode.clone());
     if mode_override && mode_str != config.default_mode {
         config.default_mode = mode_str.clone();
         config_changed = true;
     }
-    let mode = Mode::from_str_with_params(
-        &mode_str,
-        dob,
-        Some(config.lifespan_years),
-        Some(config.next_months),
-    )
-    .map_err(|e| anyhow::anyhow!(e))?;
+    let mode = Mode::from_str_with_params(&mode_str, &config.mode_params())
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    // The reference date is a one-off override and is never saved
+    let mut options = config.grid_options();
+    if let Some(ref as_of) = args.as_of {
+        options.as_of = Some(parse_date(as_of)?);
+    }
 
     println!("Generating wallpaper...");
     println!("  Mode: {:?}", mode);
@@ -209,23 +537,48 @@ fn cmd_generate(
         config.screen_width, config.screen_height
     );
     println!("  Theme: {:?}", config.theme);
+    println!("  Granularity: {:?}", config.granularity);
+    println!("  Week Start: {:?}", config.week_start);
+    if let Some(as_of) = options.as_of {
+        println!("  As of: {}", as_of);
+    }
 
     // Calculate the grid
-    let grid = WeekGrid::calculate(&mode);
+    let mut grid = WeekGrid::calculate_with(&mode, &options);
+    let survival = config.survival_curve()?;
+    let journal = Journal::load()?;
+    let warnings = grid.apply_overlays(&mode, &config, &journal, survival.as_ref(), &options)?;
+    for warning in &warnings {
+        eprintln!("Warning: {}", warning);
+    }
     println!("\n{}", grid.title);
     println!("{}", grid.subtitle);
     println!("  Grid: {} columns x {} rows", grid.columns, grid.rows);
+    if let Some(week) = grid.current_week_index.map(|i| &grid.weeks[i]) {
+        // Pregnancy and sprint weeks are counted from a fixed date, not the calendar
+        if grid.granularity == Granularity::Week
+            && !matches!(mode, Mode::Pregnancy { .. } | Mode::Sprint { .. })
+        {
+            println!(
+                "  Current week: {} ({:?} numbering)",
+                week.week_of_year, config.week_numbering
+            );
+        }
+    }
 
-    // Render the image
-    let image = render_grid(
+    // Render the image, filling the current week up to the reference date
+    let mut render_options = config.render_options();
+    render_options.as_of = options.as_of;
+    let image = render_grid_with(
         &grid,
         &config.theme,
         config.screen_width,
         config.screen_height,
+        &render_options,
     );
 
     // Determine output path
-    let output_path = if let Some(path) = output {
+    let output_path = if let Some(path) = args.output {
         path
     } else {
         Config::default_output_path()?
@@ -245,14 +598,14 @@ fn cmd_generate(
     }
 
     // Set as wallpaper unless preview mode
-    if !preview {
+    if !args.preview {
         println!("Setting as wallpaper...");
         set_wallpaper(&output_path)?;
         println!("Done! Wallpaper has been updated.");
 
         if !is_schedule_installed() {
             install_schedule()?;
-            println!("Weekly schedule installed. Wallpaper will update every Monday at 6:00 AM.");
+            println!("Daily schedule installed. Wallpaper will update every day at 6:00 AM.");
         }
     } else {
         println!("Preview mode - wallpaper not set.");
@@ -261,6 +614,12 @@ fn cmd_generate(
     Ok(())
 }
 
+/// Parse a YYYY-MM-DD date argument
+fn parse_date(value: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .with_context(|| format!("Invalid date format: {}. Use YYYY-MM-DD", value))
+}
+
 fn cmd_config_show() -> Result<()> {
     let config = Config::load().unwrap_or_default();
 
@@ -269,11 +628,53 @@ fn cmd_config_show() -> Result<()> {
     println!();
     println!("Date of Birth:     {:?}", config.dob);
     println!("Lifespan (years):  {}", config.lifespan_years);
+    println!("Sex:               {:?}", config.sex);
+    println!("Country:           {:?}", config.country);
+    println!("Survival Shading:  {}", config.survival_shading);
+    println!("Survival CSV:      {:?}", config.survival_csv);
+    println!("Data Source:       {:?}", config.data_source);
+    println!("Git Author:        {:?}", config.git_author);
+    println!("Calendar Files:    {:?}", config.ics_files);
+    println!("Category Colors:   {:?}", config.category_colors);
     println!("Theme:             {:?}", config.theme);
     println!("Screen Width:      {}", config.screen_width);
     println!("Screen Height:     {}", config.screen_height);
     println!("Default Mode:      {}", config.default_mode);
     println!("Next Months:       {}", config.next_months);
+    println!("Granularity:       {:?}", config.granularity);
+    println!("Week Start:        {:?}", config.week_start);
+    println!("Week Numbering:    {:?}", config.week_numbering);
+    println!("Range Start:       {:?}", config.range_start);
+    println!("Range End:         {:?}", config.range_end);
+    println!("Range Title:       {:?}", config.range_title);
+    println!("Month Rows:        {}", config.month_rows);
+    println!("Age Rows:          {}", config.age_rows);
+    println!(
+        "Row Labels:        {} (every {})",
+        config.row_labels, config.row_label_every
+    );
+    println!(
+        "Column Labels:     {} (every {})",
+        config.column_labels, config.column_label_every
+    );
+    println!("Current Week Fill: {:?}", config.current_week_fill);
+    println!("Retirement Age:    {}", config.retirement_age);
+    println!("Retirement Date:   {:?}", config.retirement_date);
+    println!("Vacation Weeks:    {}", config.vacation_weeks);
+    println!("Public Holidays:   {}", config.public_holidays);
+    println!("Fiscal Start:      {}", config.fiscal_start_month);
+    println!("Sprint Anchor:     {:?}", config.sprint_anchor);
+    println!("Release Date:      {:?}", config.release_date);
+    println!("Sprint Weeks:      {}", config.sprint_weeks);
+    println!("Due Date:          {:?}", config.due_date);
+    println!("Birth Date:        {:?}", config.birth_date);
+    println!("Events:            {}", config.events.len());
+    println!("Chapters:          {}", config.chapters.len());
+    println!("Targets:           {}", config.targets.len());
+    println!("People:            {}", config.people.len());
+    println!("Terms:             {}", config.terms.len());
+    println!("Recurring:         {}", config.recurring.len());
+    println!("Grouped Modes:     {}", config.grouping.len());
 
     Ok(())
 }
@@ -308,6 +709,255 @@ fn cmd_config_path() -> Result<()> {
     Ok(())
 }
 
+fn cmd_event_add(name: String, date_str: &str, color: Option<String>) -> Result<()> {
+    let mut config = Config::load().unwrap_or_default();
+
+    let date = parse_date(date_str)?;
+    config.add_event(LifeEvent {
+        name: name.clone(),
+        date,
+        color,
+    })?;
+    config.save()?;
+
+    println!("Event added: {} ({})", name, date);
+
+    Ok(())
+}
+
+fn cmd_event_list() -> Result<()> {
+    let config = Config::load().unwrap_or_default();
+
+    if config.events.is_empty() {
+        println!("No events configured.");
+        println!("Run 'liw event add <name> <YYYY-MM-DD>' to add one.");
+        return Ok(());
+    }
+
+    for event in &config.events {
+        match event.color {
+            Some(ref color) => println!("{}  {}  {}", event.date, event.name, color),
+            None => println!("{}  {}", event.date, event.name),
+        }
+    }
+
+    Ok(())
+}
+
+fn cmd_event_remove(name: &str) -> Result<()> {
+    let mut config = Config::load().unwrap_or_default();
+
+    let event = config.remove_event(name)?;
+    config.save()?;
+
+    println!("Event removed: {} ({})", event.name, event.date);
+
+    Ok(())
+}
+
+fn cmd_estimate_lifespan(
+    sex: Option<&str>,
+    country: Option<&str>,
+    dob: Option<&str>,
+    save: bool,
+) -> Result<()> {
+    let mut config = Config::load().unwrap_or_default();
+
+    if let Some(value) = dob {
+        config.set("dob", value)?;
+    }
+    if let Some(value) = sex {
+        config.set("sex", value)?;
+    }
+    if let Some(value) = country {
+        config.set("country", value)?;
+    }
+    let dob = config
+        .dob
+        .context("Date of birth is required (use --dob or 'liw config set dob')")?;
+    let sex = config
+        .sex
+        .context("Sex is required (use --sex or 'liw config set sex')")?;
+    let country = config
+        .country
+        .clone()
+        .context("Country is required (use --country or 'liw config set country')")?;
+
+    let today = chrono::Local::now().date_naive();
+    let estimate = estimate_lifespan(sex, &country, dob, today).map_err(anyhow::Error::msg)?;
+    let lifespan = estimate.lifespan_years.round().min(u8::MAX as f64) as u8;
+
+    println!("Current age:               {:.1}", estimate.age);
+    println!(
+        "Remaining life expectancy: {:.1} years",
+        estimate.remaining_years
+    );
+    println!("Estimated lifespan:        {} years", lifespan);
+
+    if save {
+        config.lifespan_years = lifespan;
+        config.save()?;
+        println!("Saved lifespan_years = {}", lifespan);
+    } else {
+        println!("Run again with --save to use it as your lifespan.");
+    }
+
+    Ok(())
+}
+
+fn cmd_target_add(name: String, date_str: &str, color: Option<String>) -> Result<()> {
+    let mut config = Config::load().unwrap_or_default();
+
+    let date = parse_date(date_str)?;
+    config.add_target(CountdownTarget {
+        name: name.clone(),
+        date,
+        color,
+    })?;
+    config.save()?;
+
+    println!("Target added: {} ({})", name, date);
+
+    Ok(())
+}
+
+fn cmd_target_list() -> Result<()> {
+    let config = Config::load().unwrap_or_default();
+
+    if config.targets.is_empty() {
+        println!("No countdown targets configured.");
+        println!("Run 'liw target add <name> <YYYY-MM-DD>' to add one.");
+        return Ok(());
+    }
+
+    for target in &config.targets {
+        match target.color {
+            Some(ref color) => println!("{}  {}  {}", target.date, target.name, color),
+            None => println!("{}  {}", target.date, target.name),
+        }
+    }
+
+    Ok(())
+}
+
+fn cmd_target_remove(name: &str) -> Result<()> {
+    let mut config = Config::load().unwrap_or_default();
+
+    let target = config.remove_target(name)?;
+    config.save()?;
+
+    println!("Target removed: {} ({})", target.name, target.date);
+
+    Ok(())
+}
+
+fn cmd_person_add(
+    name: String,
+    dob_str: &str,
+    lifespan: Option<u8>,
+    color: Option<String>,
+) -> Result<()> {
+    let mut config = Config::load().unwrap_or_default();
+
+    let dob = parse_date(dob_str)?;
+    config.add_person(Person {
+        name: name.clone(),
+        dob,
+        lifespan_years: lifespan,
+        color,
+    })?;
+    config.save()?;
+
+    println!("Person added: {} (born {})", name, dob);
+
+    Ok(())
+}
+
+fn cmd_person_list() -> Result<()> {
+    let config = Config::load().unwrap_or_default();
+
+    if config.people.is_empty() {
+        println!("No people configured.");
+        println!("Run 'liw person add <name> <YYYY-MM-DD>' to add one.");
+        return Ok(());
+    }
+
+    for person in &config.people {
+        let lifespan = person.lifespan_years.unwrap_or(config.lifespan_years);
+        match person.color {
+            Some(ref color) => println!(
+                "{}  {}  {} years  {}",
+                person.dob, person.name, lifespan, color
+            ),
+            None => println!("{}  {}  {} years", person.dob, person.name, lifespan),
+        }
+    }
+
+    Ok(())
+}
+
+fn cmd_person_remove(name: &str) -> Result<()> {
+    let mut config = Config::load().unwrap_or_default();
+
+    let person = config.remove_person(name)?;
+    config.save()?;
+
+    println!("Person removed: {} (born {})", person.name, person.dob);
+
+    Ok(())
+}
+
+fn cmd_journal_add(note: &str, rating: Option<u8>, week: Option<&str>) -> Result<()> {
+    let config = Config::load().unwrap_or_default();
+    let week = match week {
+        Some(value) => parse_week(value, config.week_start)?,
+        None => {
+            Granularity::Week.period_start(chrono::Local::now().date_naive(), config.week_start)
+        }
+    };
+
+    let mut journal = Journal::load()?;
+    journal.add(week, note, rating)?;
+    journal.save()?;
+
+    match rating {
+        Some(rating) => println!("Entry added to week of {} ({}/5): {}", week, rating, note),
+        None => println!("Entry added to week of {}: {}", week, note),
+    }
+
+    Ok(())
+}
+
+fn cmd_journal_show(week: Option<&str>) -> Result<()> {
+    let config = Config::load().unwrap_or_default();
+    let journal = Journal::load()?;
+
+    let entries: Vec<_> = match week {
+        Some(value) => {
+            let start = parse_week(value, config.week_start)?;
+            journal
+                .entries_between(start, start + chrono::Duration::days(6))
+                .collect()
+        }
+        None => journal.entries.iter().collect(),
+    };
+
+    if entries.is_empty() {
+        println!("No journal entries.");
+        println!("Run 'liw journal add <note> --rating <1-5>' to add one.");
+        return Ok(());
+    }
+
+    for entry in entries {
+        match entry.rating {
+            Some(rating) => println!("{}  {}/5  {}", entry.week, rating, entry.note),
+            None => println!("{}       {}", entry.week, entry.note),
+        }
+    }
+
+    Ok(())
+}
+
 fn cmd_schedule_install() -> Result<()> {
     install_schedule()
 }
@@ -318,10 +968,10 @@ fn cmd_schedule_uninstall() -> Result<()> {
 
 fn cmd_schedule_status() -> Result<()> {
     if is_schedule_installed() {
-        println!("Weekly schedule is INSTALLED.");
-        println!("The wallpaper will update every Monday at 6:00 AM.");
+        println!("Daily schedule is INSTALLED.");
+        println!("The wallpaper will update every day at 6:00 AM.");
     } else {
-        println!("Weekly schedule is NOT installed.");
+        println!("Daily schedule is NOT installed.");
         println!("Run 'liw schedule install' to enable automatic updates.");
     }
 
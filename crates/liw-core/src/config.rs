@@ -2,9 +2,15 @@
 //!
 //! Handles loading and saving user configuration from TOML files.
 
+use crate::data::DataSeries;
+use crate::ics::Calendar;
+use crate::lifetable::{life_table, Sex, SurvivalCurve};
+use crate::modes::{Granularity, GridOptions, ModeParams, WeekNumbering, WeekStart};
+use crate::renderer::{CurrentFill, RenderOptions};
 use anyhow::{Context, Result};
-use chrono::NaiveDate;
+use chrono::{Month, NaiveDate};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 
@@ -28,6 +34,12 @@ pub enum Theme {
         current_week: String,
         future_week: String,
         accent: String,
+        /// Heatmap color for the lowest value; `future_week` when omitted
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        scale_low: Option<String>,
+        /// Heatmap color for the highest value; `accent` when omitted
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        scale_high: Option<String>,
     },
 }
 
@@ -42,6 +54,8 @@ impl Theme {
                 future_week: [200, 195, 185, 255],
                 accent: [220, 60, 60, 255],
                 text: [30, 30, 30, 255],
+                scale_low: [215, 210, 195, 255],
+                scale_high: [40, 70, 120, 255],
             },
             Theme::TerminalGreen => ThemeColors {
                 background: [15, 15, 15, 255],
@@ -50,6 +64,8 @@ impl Theme {
                 future_week: [40, 60, 45, 255],
                 accent: [0, 255, 120, 255],
                 text: [0, 200, 100, 255],
+                scale_low: [20, 55, 30, 255],
+                scale_high: [150, 255, 190, 255],
             },
             Theme::SoftDark => ThemeColors {
                 background: [28, 28, 32, 255],
@@ -58,6 +74,8 @@ impl Theme {
                 future_week: [55, 55, 65, 255],
                 accent: [255, 120, 100, 255],
                 text: [200, 200, 210, 255],
+                scale_low: [55, 70, 65, 255],
+                scale_high: [110, 220, 150, 255],
             },
             Theme::SunsetGradient => ThemeColors {
                 background: [25, 25, 35, 255],
@@ -66,6 +84,8 @@ impl Theme {
                 future_week: [60, 60, 90, 255],
                 accent: [255, 180, 100, 255],
                 text: [240, 240, 250, 255],
+                scale_low: [80, 60, 100, 255],
+                scale_high: [255, 210, 100, 255],
             },
             Theme::Custom {
                 background,
@@ -73,6 +93,8 @@ impl Theme {
                 current_week,
                 future_week,
                 accent,
+                scale_low,
+                scale_high,
             } => ThemeColors {
                 background: parse_hex_color(background),
                 past_week: parse_hex_color(past_week),
@@ -80,6 +102,8 @@ impl Theme {
                 future_week: parse_hex_color(future_week),
                 accent: parse_hex_color(accent),
                 text: [255, 255, 255, 255],
+                scale_low: parse_hex_color(scale_low.as_deref().unwrap_or(future_week)),
+                scale_high: parse_hex_color(scale_high.as_deref().unwrap_or(accent)),
             },
         }
     }
@@ -94,10 +118,13 @@ pub struct ThemeColors {
     pub future_week: [u8; 4],
     pub accent: [u8; 4],
     pub text: [u8; 4],
+    /// Heatmap color scale, from the lowest value to the highest
+    pub scale_low: [u8; 4],
+    pub scale_high: [u8; 4],
 }
 
 /// Parse a hex color string like "#FF5500" into RGBA
-fn parse_hex_color(hex: &str) -> [u8; 4] {
+pub(crate) fn parse_hex_color(hex: &str) -> [u8; 4] {
     let hex = hex.trim_start_matches('#');
     if hex.len() >= 6 {
         let r = u8::from_str_radix(&hex[0..2], 16).unwrap_or(128);
@@ -114,6 +141,119 @@ fn parse_hex_color(hex: &str) -> [u8; 4] {
     }
 }
 
+/// A dated life event highlighted in the grid
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LifeEvent {
+    /// Short name shown in the legend
+    pub name: String,
+    /// Date the event happened (or will happen)
+    pub date: NaiveDate,
+    /// Optional hex color; a palette color is used when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+}
+
+/// A named deadline counted down to in countdown mode
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CountdownTarget {
+    /// Short name shown in the subtitle and legend
+    pub name: String,
+    /// Target date
+    pub date: NaiveDate,
+    /// Optional hex color; a palette color is used when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+}
+
+/// A named period of life painted as a colored band across the grid
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Chapter {
+    /// Short name shown in the legend
+    pub name: String,
+    /// First day of the chapter
+    pub start: NaiveDate,
+    /// Last day of the chapter (inclusive)
+    pub end: NaiveDate,
+    /// Optional hex color; a palette color is used when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+}
+
+/// A family member or partner shown in family mode
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Person {
+    /// Name shown in the legend
+    pub name: String,
+    /// Date of birth
+    pub dob: NaiveDate,
+    /// Expected lifespan in years; the config's `lifespan_years` is used when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lifespan_years: Option<u8>,
+    /// Optional hex color; a palette color is used when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+}
+
+/// Something that happens a few times a year, counted in tail-end mode
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RecurringItem {
+    /// Short name, like "Summers" or "Visits to parents"
+    pub name: String,
+    /// How many times it happens per year
+    pub per_year: f64,
+    /// First day it counts from; the date of birth is used when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<NaiveDate>,
+    /// Last day it can happen; the end of the expected lifespan is used when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<NaiveDate>,
+    /// Optional hex color; a palette color is used when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+}
+
+/// Blocks of rows and columns set apart by wider gaps, configured per mode
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct Grouping {
+    /// Rows per block, such as 10 for decades of life; 0 keeps the mode's
+    #[serde(default)]
+    pub rows: usize,
+    /// Columns per block, such as 13 for quarters; 0 keeps the mode's
+    #[serde(default)]
+    pub columns: usize,
+    /// Caption each block of rows with its first and last row labels
+    #[serde(default)]
+    pub captions: bool,
+}
+
+/// An academic term shown in term mode
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Term {
+    /// Name shown as the grid title
+    pub name: String,
+    /// First day of the term
+    pub start: NaiveDate,
+    /// Last day of the term (inclusive)
+    pub end: NaiveDate,
+    /// Breaks and exam periods within the term
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub breaks: Vec<TermBreak>,
+}
+
+/// A non-teaching period within a term
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TermBreak {
+    /// Short name, like "Reading week"
+    pub name: String,
+    /// First day of the break
+    pub start: NaiveDate,
+    /// Last day of the break (inclusive)
+    pub end: NaiveDate,
+    /// Whether this is an exam period rather than a holiday
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub exam: bool,
+}
+
 /// User configuration for Life in Weeks
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -122,6 +262,27 @@ pub struct Config {
     /// Expected lifespan in years
     #[serde(default = "default_lifespan")]
     pub lifespan_years: u8,
+    /// Sex used to pick a life table column
+    pub sex: Option<Sex>,
+    /// Country code used to pick a life table
+    pub country: Option<String>,
+    /// Shade future weeks in life mode by the probability of being alive
+    #[serde(default)]
+    pub survival_shading: bool,
+    /// CSV file of `age,survivors` used instead of the embedded life tables
+    pub survival_csv: Option<PathBuf>,
+    /// Heatmap data shown on past weeks: a CSV or JSON file of dated values,
+    /// or `git:` and repository paths to count commits
+    pub data_source: Option<String>,
+    /// Author email whose commits a git data source counts; each
+    /// repository's `user.email` is used when omitted
+    pub git_author: Option<String>,
+    /// iCalendar files whose events mark weeks in the grid
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ics_files: Vec<PathBuf>,
+    /// Hex colors for calendar event categories, by category name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub category_colors: BTreeMap<String, String>,
     /// Visual theme
     #[serde(default)]
     pub theme: Theme,
@@ -137,6 +298,88 @@ pub struct Config {
     /// Number of months for next-months mode
     #[serde(default = "default_months")]
     pub next_months: u8,
+    /// Time unit of a single cell
+    #[serde(default)]
+    pub granularity: Granularity,
+    /// Day on which weeks begin
+    #[serde(default)]
+    pub week_start: WeekStart,
+    /// Week numbering scheme used for labels
+    #[serde(default)]
+    pub week_numbering: WeekNumbering,
+    /// First day for range mode
+    pub range_start: Option<NaiveDate>,
+    /// Last day for range mode
+    pub range_end: Option<NaiveDate>,
+    /// Custom title for range mode
+    pub range_title: Option<String>,
+    /// Lay out full-year mode with one row per month
+    #[serde(default)]
+    pub month_rows: bool,
+    /// Lay out life mode with one row per year of age, starting on birthdays
+    #[serde(default)]
+    pub age_rows: bool,
+    /// Label rows in the left margin with their year, age or month
+    #[serde(default)]
+    pub row_labels: bool,
+    /// Label every this many rows
+    #[serde(default = "default_label_every")]
+    pub row_label_every: usize,
+    /// Number columns along the top of the grid
+    #[serde(default)]
+    pub column_labels: bool,
+    /// Spacing of column numbers (1, then every N)
+    #[serde(default = "default_label_every")]
+    pub column_label_every: usize,
+    /// How far the current week is filled: solid, horizontal or radial
+    #[serde(default)]
+    pub current_week_fill: CurrentFill,
+    /// Row and column blocks by mode name (`life`, `full-year`, ...)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub grouping: BTreeMap<String, Grouping>,
+    /// First month (1-12) of the fiscal year for fiscal mode
+    #[serde(default = "default_fiscal_start_month")]
+    pub fiscal_start_month: u32,
+    /// Age at retirement for retirement mode
+    #[serde(default = "default_retirement_age")]
+    pub retirement_age: u8,
+    /// Retirement date for retirement mode; wins over `retirement_age`
+    pub retirement_date: Option<NaiveDate>,
+    /// Vacation weeks per year, subtracted from working weeks
+    #[serde(default)]
+    pub vacation_weeks: u8,
+    /// Public holidays (days) per year, subtracted from working weeks
+    #[serde(default)]
+    pub public_holidays: u8,
+    /// First day of sprint 1 for sprint mode
+    pub sprint_anchor: Option<NaiveDate>,
+    /// Release date that ends sprint mode
+    pub release_date: Option<NaiveDate>,
+    /// Length of a sprint in weeks
+    #[serde(default = "default_sprint_weeks")]
+    pub sprint_weeks: u8,
+    /// Due date for pregnancy mode
+    pub due_date: Option<NaiveDate>,
+    /// Birth date for pregnancy mode, when it differs from the due date
+    pub birth_date: Option<NaiveDate>,
+    /// Life events shown as highlighted weeks
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<LifeEvent>,
+    /// Life chapters shown as colored bands
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chapters: Vec<Chapter>,
+    /// Deadlines for countdown mode
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub targets: Vec<CountdownTarget>,
+    /// People shown together in family mode
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub people: Vec<Person>,
+    /// Academic terms for term mode
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub terms: Vec<Term>,
+    /// Recurring things counted in tail-end mode
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub recurring: Vec<RecurringItem>,
 }
 
 fn default_lifespan() -> u8 {
@@ -151,17 +394,67 @@ fn default_height() -> u32 {
 fn default_months() -> u8 {
     6
 }
+fn default_fiscal_start_month() -> u32 {
+    1
+}
+fn default_retirement_age() -> u8 {
+    65
+}
+fn default_sprint_weeks() -> u8 {
+    2
+}
+fn default_label_every() -> usize {
+    10
+}
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             dob: None,
             lifespan_years: default_lifespan(),
+            sex: None,
+            country: None,
+            survival_shading: false,
+            survival_csv: None,
+            data_source: None,
+            git_author: None,
+            ics_files: Vec::new(),
+            category_colors: BTreeMap::new(),
             theme: Theme::default(),
             screen_width: default_width(),
             screen_height: default_height(),
             default_mode: "year-end".to_string(),
             next_months: default_months(),
+            granularity: Granularity::default(),
+            week_start: WeekStart::default(),
+            week_numbering: WeekNumbering::default(),
+            range_start: None,
+            range_end: None,
+            range_title: None,
+            month_rows: false,
+            age_rows: false,
+            row_labels: false,
+            row_label_every: default_label_every(),
+            column_labels: false,
+            column_label_every: default_label_every(),
+            current_week_fill: CurrentFill::default(),
+            grouping: BTreeMap::new(),
+            fiscal_start_month: default_fiscal_start_month(),
+            retirement_age: default_retirement_age(),
+            retirement_date: None,
+            vacation_weeks: 0,
+            public_holidays: 0,
+            sprint_anchor: None,
+            release_date: None,
+            sprint_weeks: default_sprint_weeks(),
+            due_date: None,
+            birth_date: None,
+            events: Vec::new(),
+            chapters: Vec::new(),
+            targets: Vec::new(),
+            people: Vec::new(),
+            terms: Vec::new(),
+            recurring: Vec::new(),
         }
     }
 }
@@ -233,6 +526,52 @@ impl Config {
                     .parse()
                     .with_context(|| format!("Invalid lifespan: {}", value))?;
             }
+            "sex" => {
+                self.sex = Some(Sex::parse(value).map_err(anyhow::Error::msg)?);
+            }
+            "country" => {
+                let table = life_table(value).map_err(anyhow::Error::msg)?;
+                self.country = Some(table.code.to_string());
+            }
+            "survival_shading" | "survival" => {
+                self.survival_shading = value.parse().with_context(|| {
+                    format!("Invalid survival_shading: {}. Use true or false", value)
+                })?;
+            }
+            "survival_csv" => {
+                self.survival_csv =
+                    Some(PathBuf::from(value)).filter(|p| !p.as_os_str().is_empty());
+            }
+            "data_source" | "data" => {
+                self.data_source = Some(value.to_string()).filter(|v| !v.is_empty());
+            }
+            "git_author" | "author" => {
+                self.git_author = Some(value.to_string()).filter(|v| !v.is_empty());
+            }
+            "ics_files" | "ics" => {
+                // Comma-separated paths; an empty value clears the list
+                self.ics_files = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|p| !p.is_empty())
+                    .map(PathBuf::from)
+                    .collect();
+            }
+            "category_color" => {
+                // CATEGORY=#RRGGBB sets a color, CATEGORY= removes it
+                let (category, color) = value.split_once('=').with_context(|| {
+                    format!("Invalid category_color: {}. Use CATEGORY=#RRGGBB", value)
+                })?;
+                let category = category.trim().to_string();
+                match color.trim() {
+                    "" => {
+                        self.category_colors.remove(&category);
+                    }
+                    color => {
+                        self.category_colors.insert(category, color.to_string());
+                    }
+                }
+            }
             "theme" => {
                 self.theme = match value.to_lowercase().as_str() {
                     "minimal" | "minimal_ink" | "minimal-ink" => Theme::MinimalInk,
@@ -263,10 +602,307 @@ impl Config {
                     .parse()
                     .with_context(|| format!("Invalid months: {}", value))?;
             }
+            "granularity" | "unit" => {
+                self.granularity = Granularity::parse(value).map_err(anyhow::Error::msg)?;
+            }
+            "week_start" => {
+                self.week_start = WeekStart::parse(value).map_err(anyhow::Error::msg)?;
+            }
+            "week_numbering" => {
+                self.week_numbering = WeekNumbering::parse(value).map_err(anyhow::Error::msg)?;
+            }
+            "range_start" | "start" => {
+                let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                    .with_context(|| format!("Invalid date format: {}. Use YYYY-MM-DD", value))?;
+                self.range_start = Some(date);
+            }
+            "range_end" | "end" => {
+                let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                    .with_context(|| format!("Invalid date format: {}. Use YYYY-MM-DD", value))?;
+                self.range_end = Some(date);
+            }
+            "range_title" | "title" => {
+                self.range_title = Some(value.to_string()).filter(|t| !t.is_empty());
+            }
+            "month_rows" => {
+                self.month_rows = value
+                    .parse()
+                    .with_context(|| format!("Invalid month_rows: {}. Use true or false", value))?;
+            }
+            "age_rows" => {
+                self.age_rows = value
+                    .parse()
+                    .with_context(|| format!("Invalid age_rows: {}. Use true or false", value))?;
+            }
+            "row_labels" => {
+                self.row_labels = value
+                    .parse()
+                    .with_context(|| format!("Invalid row_labels: {}. Use true or false", value))?;
+            }
+            "row_label_every" => {
+                self.row_label_every = value
+                    .parse()
+                    .ok()
+                    .filter(|&every| every > 0)
+                    .with_context(|| format!("Invalid row_label_every: {}", value))?;
+            }
+            "column_labels" => {
+                self.column_labels = value.parse().with_context(|| {
+                    format!("Invalid column_labels: {}. Use true or false", value)
+                })?;
+            }
+            "column_label_every" => {
+                self.column_label_every = value
+                    .parse()
+                    .ok()
+                    .filter(|&every| every > 0)
+                    .with_context(|| format!("Invalid column_label_every: {}", value))?;
+            }
+            "current_week_fill" | "fill" => {
+                self.current_week_fill = CurrentFill::parse(value).map_err(anyhow::Error::msg)?;
+            }
+            "fiscal_start_month" | "fiscal_start" => {
+                // Accept a month number or name ("7", "jul", "July")
+                let month = value
+                    .parse::<u32>()
+                    .ok()
+                    .or_else(|| value.parse::<Month>().ok().map(|m| m.number_from_month()))
+                    .filter(|m| (1..=12).contains(m));
+                self.fiscal_start_month = month.with_context(|| {
+                    format!(
+                        "Invalid fiscal start month: {}. Use 1-12 or a month name",
+                        value
+                    )
+                })?;
+            }
+            "retirement_age" => {
+                self.retirement_age = value
+                    .parse()
+                    .with_context(|| format!("Invalid retirement_age: {}", value))?;
+            }
+            "retirement_date" | "retire_on" => {
+                if value.is_empty() {
+                    self.retirement_date = None;
+                } else {
+                    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").with_context(|| {
+                        format!("Invalid date format: {}. Use YYYY-MM-DD", value)
+                    })?;
+                    self.retirement_date = Some(date);
+                }
+            }
+            "vacation_weeks" => {
+                self.vacation_weeks = value
+                    .parse()
+                    .ok()
+                    .filter(|&weeks| weeks <= 52)
+                    .with_context(|| format!("Invalid vacation_weeks: {}", value))?;
+            }
+            "public_holidays" | "holidays" => {
+                self.public_holidays = value
+                    .parse()
+                    .with_context(|| format!("Invalid public_holidays: {}", value))?;
+            }
+            "sprint_anchor" | "anchor" => {
+                let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                    .with_context(|| format!("Invalid date format: {}. Use YYYY-MM-DD", value))?;
+                self.sprint_anchor = Some(date);
+            }
+            "release_date" | "release" => {
+                let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                    .with_context(|| format!("Invalid date format: {}. Use YYYY-MM-DD", value))?;
+                self.release_date = Some(date);
+            }
+            "sprint_weeks" | "sprint_length" => {
+                self.sprint_weeks = value
+                    .parse()
+                    .ok()
+                    .filter(|&weeks| weeks > 0)
+                    .with_context(|| format!("Invalid sprint_weeks: {}", value))?;
+            }
+            "due_date" | "due" => {
+                let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                    .with_context(|| format!("Invalid date format: {}. Use YYYY-MM-DD", value))?;
+                self.due_date = Some(date);
+            }
+            "birth_date" | "born" => {
+                if value.is_empty() {
+                    self.birth_date = None;
+                } else {
+                    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").with_context(|| {
+                        format!("Invalid date format: {}. Use YYYY-MM-DD", value)
+                    })?;
+                    self.birth_date = Some(date);
+                }
+            }
             _ => anyhow::bail!("Unknown config key: {}", key),
         }
         Ok(())
     }
+
+    /// Collect the mode parameters stored in this config
+    pub fn mode_params(&self) -> ModeParams {
+        ModeParams {
+            dob: self.dob,
+            lifespan: Some(self.lifespan_years),
+            age_rows: self.age_rows,
+            months: Some(self.next_months),
+            start: self.range_start,
+            end: self.range_end,
+            title: self.range_title.clone(),
+            targets: self.targets.clone(),
+            month_rows: self.month_rows,
+            people: self.people.clone(),
+            terms: self.terms.clone(),
+            recurring: self.recurring.clone(),
+            fiscal_start_month: Some(self.fiscal_start_month),
+            retirement_age: Some(self.retirement_age),
+            retirement_date: self.retirement_date,
+            vacation_weeks: Some(self.vacation_weeks),
+            holiday_days: Some(self.public_holidays),
+            sprint_anchor: self.sprint_anchor,
+            release: self.release_date,
+            sprint_weeks: Some(self.sprint_weeks),
+            due: self.due_date,
+            conceived: None,
+            born: self.birth_date,
+        }
+    }
+
+    /// Collect the calendar options stored in this config
+    pub fn grid_options(&self) -> GridOptions {
+        GridOptions {
+            granularity: self.granularity,
+            week_start: self.week_start,
+            week_numbering: self.week_numbering,
+            as_of: None,
+        }
+    }
+
+    /// Collect the label and fill options stored in this config
+    pub fn render_options(&self) -> RenderOptions {
+        RenderOptions {
+            row_labels: self.row_labels,
+            row_label_every: self.row_label_every,
+            column_labels: self.column_labels,
+            column_label_every: self.column_label_every,
+            current_fill: self.current_week_fill,
+            as_of: None,
+        }
+    }
+
+    /// Survival curve for shading, or `None` when survival shading is off
+    ///
+    /// A configured CSV file wins over the embedded life tables.
+    pub fn survival_curve(&self) -> Result<Option<SurvivalCurve>> {
+        if !self.survival_shading {
+            return Ok(None);
+        }
+
+        if let Some(ref path) = self.survival_csv {
+            let content = fs::read_to_string(path)
+                .with_context(|| format!("Failed to read survival CSV: {:?}", path))?;
+            let curve = SurvivalCurve::parse_csv(&content)
+                .map_err(anyhow::Error::msg)
+                .with_context(|| format!("Invalid survival CSV: {:?}", path))?;
+            return Ok(Some(curve));
+        }
+
+        let (Some(sex), Some(country)) = (self.sex, self.country.as_deref()) else {
+            anyhow::bail!(
+                "Survival shading needs 'sex' and 'country' (see 'liw config set') or 'survival_csv'"
+            );
+        };
+        let table = life_table(country).map_err(anyhow::Error::msg)?;
+        Ok(Some(table.curve(sex)))
+    }
+
+    /// Heatmap data from the configured data source, if any
+    pub fn data_series(&self) -> Result<Option<DataSeries>> {
+        self.data_source
+            .as_deref()
+            .map(|source| DataSeries::from_source(source, self.git_author.as_deref()))
+            .transpose()
+    }
+
+    /// Events from the configured calendar files
+    pub fn calendars(&self) -> Result<Vec<Calendar>> {
+        self.ics_files
+            .iter()
+            .map(|path| Calendar::load(path))
+            .collect()
+    }
+
+    /// Add a life event, keeping events sorted by date
+    pub fn add_event(&mut self, event: LifeEvent) -> Result<()> {
+        if self
+            .events
+            .iter()
+            .any(|e| e.name.eq_ignore_ascii_case(&event.name))
+        {
+            anyhow::bail!("An event named {:?} already exists", event.name);
+        }
+        self.events.push(event);
+        self.events.sort_by_key(|e| e.date);
+        Ok(())
+    }
+
+    /// Remove a life event by name (case-insensitive)
+    pub fn remove_event(&mut self, name: &str) -> Result<LifeEvent> {
+        let index = self
+            .events
+            .iter()
+            .position(|e| e.name.eq_ignore_ascii_case(name))
+            .with_context(|| format!("No event named {:?}", name))?;
+        Ok(self.events.remove(index))
+    }
+
+    /// Add a countdown target, keeping targets sorted by date
+    pub fn add_target(&mut self, target: CountdownTarget) -> Result<()> {
+        if self
+            .targets
+            .iter()
+            .any(|t| t.name.eq_ignore_ascii_case(&target.name))
+        {
+            anyhow::bail!("A target named {:?} already exists", target.name);
+        }
+        self.targets.push(target);
+        self.targets.sort_by_key(|t| t.date);
+        Ok(())
+    }
+
+    /// Remove a countdown target by name (case-insensitive)
+    pub fn remove_target(&mut self, name: &str) -> Result<CountdownTarget> {
+        let index = self
+            .targets
+            .iter()
+            .position(|t| t.name.eq_ignore_ascii_case(name))
+            .with_context(|| format!("No target named {:?}", name))?;
+        Ok(self.targets.remove(index))
+    }
+
+    /// Add a person to family mode, keeping people sorted by date of birth
+    pub fn add_person(&mut self, person: Person) -> Result<()> {
+        if self
+            .people
+            .iter()
+            .any(|p| p.name.eq_ignore_ascii_case(&person.name))
+        {
+            anyhow::bail!("A person named {:?} already exists", person.name);
+        }
+        self.people.push(person);
+        self.people.sort_by_key(|p| p.dob);
+        Ok(())
+    }
+
+    /// Remove a person from family mode by name (case-insensitive)
+    pub fn remove_person(&mut self, name: &str) -> Result<Person> {
+        let index = self
+            .people
+            .iter()
+            .position(|p| p.name.eq_ignore_ascii_case(name))
+            .with_context(|| format!("No person named {:?}", name))?;
+        Ok(self.people.remove(index))
+    }
 }
 
 #[cfg(test)]
@@ -286,4 +922,172 @@ mod tests {
         assert_eq!(config.lifespan_years, 80);
         assert_eq!(config.theme, Theme::SoftDark);
     }
+
+    #[test]
+    fn test_events_roundtrip() {
+        let mut config = Config::default();
+        config
+            .add_event(LifeEvent {
+                name: "Wedding".to_string(),
+                date: NaiveDate::from_ymd_opt(2020, 6, 13).unwrap(),
+                color: Some("#FFD700".to_string()),
+            })
+            .unwrap();
+        config
+            .add_event(LifeEvent {
+                name: "Graduation".to_string(),
+                date: NaiveDate::from_ymd_opt(2016, 7, 1).unwrap(),
+                color: None,
+            })
+            .unwrap();
+        assert_eq!(config.events[0].name, "Graduation");
+        assert!(config
+            .add_event(LifeEvent {
+                name: "wedding".to_string(),
+                date: NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(),
+                color: None,
+            })
+            .is_err());
+
+        let parsed: Config = toml::from_str(&toml::to_string_pretty(&config).unwrap()).unwrap();
+        assert_eq!(parsed.events, config.events);
+
+        assert_eq!(config.remove_event("WEDDING").unwrap().name, "Wedding");
+        assert!(config.remove_event("Wedding").is_err());
+    }
+
+    #[test]
+    fn test_parse_chapters() {
+        let config: Config = toml::from_str(
+            r##"
+            [[chapters]]
+            name = "University"
+            start = "2008-09-01"
+            end = "2012-06-30"
+            color = "#4A90D9"
+
+            [[chapters]]
+            name = "Berlin years"
+            start = "2012-07-01"
+            end = "2018-12-31"
+            "##,
+        )
+        .unwrap();
+
+        assert_eq!(config.chapters.len(), 2);
+        assert_eq!(config.chapters[0].color.as_deref(), Some("#4A90D9"));
+        assert_eq!(config.chapters[1].color, None);
+    }
+
+    #[test]
+    fn test_set_calendar_options() {
+        let mut config = Config::default();
+        config.set("ics", "/tmp/work.ics, /tmp/home.ics").unwrap();
+        config.set("category_color", "Vacation=#88C0D0").unwrap();
+        config.set("category_color", "On-call=#BF616A").unwrap();
+        config.set("category_color", "On-call=").unwrap();
+        assert_eq!(
+            config.ics_files,
+            [
+                PathBuf::from("/tmp/work.ics"),
+                PathBuf::from("/tmp/home.ics")
+            ]
+        );
+        assert!(config.set("category_color", "Vacation").is_err());
+
+        let parsed: Config = toml::from_str(&toml::to_string_pretty(&config).unwrap()).unwrap();
+        assert_eq!(parsed.ics_files, config.ics_files);
+        assert_eq!(
+            parsed.category_colors.into_iter().collect::<Vec<_>>(),
+            [("Vacation".to_string(), "#88C0D0".to_string())]
+        );
+
+        config.set("ics_files", "").unwrap();
+        assert!(config.ics_files.is_empty());
+    }
+
+    #[test]
+    fn test_parse_grouping() {
+        let config: Config = toml::from_str(
+            r#"
+            [grouping.life]
+            rows = 10
+            columns = 13
+            captions = true
+
+            [grouping.full-year]
+            columns = 13
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.grouping["life"],
+            Grouping {
+                rows: 10,
+                columns: 13,
+                captions: true,
+            }
+        );
+        assert_eq!(config.grouping["full-year"].rows, 0);
+        assert!(Config::default().grouping.is_empty());
+    }
+
+    #[test]
+    fn test_set_week_start() {
+        let mut config = Config::default();
+        config.set("week_start", "Sunday").unwrap();
+        config.set("week_numbering", "us").unwrap();
+        assert!(config.set("week_start", "friday").is_err());
+
+        let options = config.grid_options();
+        assert_eq!(options.week_start, WeekStart::Sunday);
+        assert_eq!(options.week_numbering, WeekNumbering::Us);
+
+        let parsed: Config = toml::from_str(&toml::to_string_pretty(&config).unwrap()).unwrap();
+        assert_eq!(parsed.week_start, WeekStart::Sunday);
+    }
+
+    #[test]
+    fn test_set_current_week_fill() {
+        let mut config = Config::default();
+        assert_eq!(config.render_options().current_fill, CurrentFill::Solid);
+        config.set("fill", "pie").unwrap();
+        assert_eq!(config.current_week_fill, CurrentFill::Radial);
+        config.set("current_week_fill", "horizontal").unwrap();
+        assert!(config.set("current_week_fill", "diagonal").is_err());
+        assert_eq!(
+            config.render_options().current_fill,
+            CurrentFill::Horizontal
+        );
+
+        let parsed: Config = toml::from_str(&toml::to_string_pretty(&config).unwrap()).unwrap();
+        assert_eq!(parsed.current_week_fill, CurrentFill::Horizontal);
+    }
+
+    #[test]
+    fn test_set_fiscal_start_month() {
+        let mut config = Config::default();
+        assert_eq!(config.mode_params().fiscal_start_month, Some(1));
+        config.set("fiscal_start_month", "July").unwrap();
+        assert_eq!(config.fiscal_start_month, 7);
+        config.set("fiscal_start", "4").unwrap();
+        assert_eq!(config.fiscal_start_month, 4);
+        assert!(config.set("fiscal_start_month", "13").is_err());
+    }
+
+    #[test]
+    fn test_survival_curve() {
+        let mut config = Config::default();
+        assert!(config.survival_curve().unwrap().is_none());
+
+        config.set("survival_shading", "true").unwrap();
+        assert!(config.survival_curve().is_err());
+
+        config.set("sex", "female").unwrap();
+        config.set("country", "japan").unwrap();
+        assert_eq!(config.country.as_deref(), Some("JP"));
+        assert!(config.survival_curve().unwrap().is_some());
+        assert!(config.set("country", "Atlantis").is_err());
+    }
 }
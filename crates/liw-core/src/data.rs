@@ -0,0 +1,302 @@
+//! Numeric data sources for heatmaps
+//!
+//! Loads dated values (hours worked, steps, km run, pages read) from CSV or
+//! JSON files, or counts commits in local git repositories. Values are summed
+//! into grid cells by `WeekGrid::apply_data`, so a source can hold one value
+//! per day or one per week.
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Prefix of a data source that counts git commits, as in `git:/path/to/repo`
+const GIT_PREFIX: &str = "git:";
+
+/// Dated values shown as a heatmap
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DataSeries {
+    /// What the values measure, shown in the legend
+    pub label: String,
+    /// `(date, value)` pairs sorted by date
+    pub points: Vec<(NaiveDate, f64)>,
+}
+
+/// JSON layouts accepted by `DataSeries::parse_json`
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonData {
+    /// `[{"date": "2024-03-04", "value": 7.5}, ...]`
+    Rows(Vec<JsonRow>),
+    /// `{"2024-03-04": 7.5, ...}`
+    Map(BTreeMap<NaiveDate, f64>),
+}
+
+#[derive(Deserialize)]
+struct JsonRow {
+    date: NaiveDate,
+    value: f64,
+}
+
+impl DataSeries {
+    /// Load a data source: `git:` and comma-separated repository paths, or
+    /// the path of a CSV or JSON file
+    ///
+    /// Git commits are counted for `author` (an email address), or else for
+    /// each repository's configured `user.email`.
+    pub fn from_source(source: &str, author: Option<&str>) -> Result<Self> {
+        match source.strip_prefix(GIT_PREFIX) {
+            Some(repos) => {
+                let repos: Vec<PathBuf> = repos
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|repo| !repo.is_empty())
+                    .map(PathBuf::from)
+                    .collect();
+                Self::from_git(&repos, author)
+            }
+            None => Self::load(Path::new(source)),
+        }
+    }
+
+    /// Count commits per day in local git repositories, across all branches
+    ///
+    /// Runs `git log` against each repository, so it works offline. Only
+    /// commits by `author`, or else by each repository's `user.email`, count.
+    pub fn from_git(repos: &[PathBuf], author: Option<&str>) -> Result<Self> {
+        if repos.is_empty() {
+            anyhow::bail!("No git repositories given. Use git:/path/to/repo");
+        }
+
+        let mut counts = BTreeMap::new();
+        for repo in repos {
+            // Each repository has its own user.email, so filter it on its own
+            let author = match author {
+                Some(author) => author.to_string(),
+                None => git_user_email(repo).with_context(|| {
+                    format!(
+                        "No author for {:?}: pass --author or set git's user.email",
+                        repo
+                    )
+                })?,
+            };
+            let output = Command::new("git")
+                .arg("-C")
+                .arg(repo)
+                .args([
+                    "log",
+                    "--all",
+                    "--no-merges",
+                    "--format=%ae %ad",
+                    "--date=short",
+                ])
+                .output()
+                .context("Failed to execute git")?;
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                anyhow::bail!(
+                    "Failed to read git history of {:?}: {}",
+                    repo,
+                    stderr.trim()
+                );
+            }
+            count_commits(
+                &String::from_utf8_lossy(&output.stdout),
+                &author,
+                &mut counts,
+            );
+        }
+
+        Ok(Self::new(
+            "commits".to_string(),
+            counts.into_iter().collect(),
+        ))
+    }
+
+    /// Load a `.json` or `.csv` file, labeled by the CSV header or the file name
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read data file: {:?}", path))?;
+        let label = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().replace(['_', '-'], " "))
+            .unwrap_or_default();
+        let is_json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+
+        let series = if is_json {
+            Self::parse_json(&label, &content)
+        } else {
+            Self::parse_csv(&label, &content)
+        };
+        series.with_context(|| format!("Invalid data file: {:?}", path))
+    }
+
+    /// Parse CSV lines of `date,value`
+    ///
+    /// A header line names the values (e.g. `date,km` is labeled "km");
+    /// blank lines and `#` comments are skipped.
+    pub fn parse_csv(label: &str, content: &str) -> Result<Self> {
+        let mut label = label.to_string();
+        let mut points = Vec::new();
+
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split(',').map(str::trim);
+            let (Some(date), Some(value)) = (fields.next(), fields.next()) else {
+                anyhow::bail!("Line {}: expected date,value", number + 1);
+            };
+            let (Ok(date), Ok(value)) = (
+                NaiveDate::parse_from_str(date, "%Y-%m-%d"),
+                value.parse::<f64>(),
+            ) else {
+                if points.is_empty() {
+                    // Header row
+                    if !value.is_empty() {
+                        label = value.to_string();
+                    }
+                    continue;
+                }
+                anyhow::bail!("Line {}: invalid date or number in {:?}", number + 1, line);
+            };
+            points.push((date, value));
+        }
+
+        Ok(Self::new(label, points))
+    }
+
+    /// Parse a JSON array of `{"date", "value"}` objects or an object keyed by date
+    pub fn parse_json(label: &str, content: &str) -> Result<Self> {
+        let points = match serde_json::from_str(content)? {
+            JsonData::Rows(rows) => rows.into_iter().map(|r| (r.date, r.value)).collect(),
+            JsonData::Map(map) => map.into_iter().collect(),
+        };
+        Ok(Self::new(label.to_string(), points))
+    }
+
+    /// Build a series from unsorted points
+    pub fn new(label: String, mut points: Vec<(NaiveDate, f64)>) -> Self {
+        points.sort_by_key(|&(date, _)| date);
+        Self { label, points }
+    }
+
+    /// Sum of the values dated between `start` and `end` (inclusive), or
+    /// `None` when there are none
+    pub fn total_between(&self, start: NaiveDate, end: NaiveDate) -> Option<f64> {
+        let from = self.points.partition_point(|&(date, _)| date < start);
+        let to = self.points.partition_point(|&(date, _)| date <= end);
+        let values = &self.points[from..to];
+        (!values.is_empty()).then(|| values.iter().map(|&(_, value)| value).sum())
+    }
+}
+
+/// The `user.email` git uses for commits in `repo`, if any
+fn git_user_email(repo: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["config", "user.email"])
+        .output()
+        .ok()?;
+    let email = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !email.is_empty()).then_some(email)
+}
+
+/// Add up commits per day by `author` in `git log` lines of `email date`
+fn count_commits(log: &str, author: &str, counts: &mut BTreeMap<NaiveDate, f64>) {
+    for line in log.lines() {
+        let Some((email, date)) = line.trim().rsplit_once(' ') else {
+            continue;
+        };
+        if !email.eq_ignore_ascii_case(author) {
+            continue;
+        }
+        if let Ok(date) = NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+            *counts.entry(date).or_default() += 1.0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let series = DataSeries::parse_csv(
+            "running",
+            "# weekly runs\ndate,km\n2024-03-06,5\n2024-03-04, 7.5\n\n2024-03-12,10\n",
+        )
+        .unwrap();
+        assert_eq!(series.label, "km");
+        assert_eq!(series.points[0], (date(3, 4), 7.5));
+        assert_eq!(series.total_between(date(3, 4), date(3, 10)), Some(12.5));
+        assert_eq!(series.total_between(date(3, 11), date(3, 17)), Some(10.0));
+        assert_eq!(series.total_between(date(3, 18), date(3, 24)), None);
+
+        assert!(DataSeries::parse_csv("x", "2024-03-04,1\n2024-03-05,lots").is_err());
+        assert!(DataSeries::parse_csv("x", "2024-03-04").is_err());
+    }
+
+    #[test]
+    fn test_parse_json() {
+        let rows = DataSeries::parse_json(
+            "steps",
+            r#"[{"date": "2024-03-05", "value": 9000}, {"date": "2024-03-04", "value": 4000}]"#,
+        )
+        .unwrap();
+        let map =
+            DataSeries::parse_json("steps", r#"{"2024-03-04": 4000, "2024-03-05": 9000}"#).unwrap();
+        assert_eq!(rows, map);
+        assert_eq!(rows.total_between(date(3, 4), date(3, 10)), Some(13000.0));
+        assert!(DataSeries::parse_json("steps", r#"{"date": "soon"}"#).is_err());
+    }
+
+    #[test]
+    fn test_count_commits() {
+        let log = "me@example.com 2024-03-05\n\
+                   Me@Example.com 2024-03-05\n\
+                   other@example.com 2024-03-05\n\
+                   me@example.com 2024-03-12\n\
+                   garbage\n";
+        let mut mine = BTreeMap::new();
+        count_commits(log, "me@example.com", &mut mine);
+        assert_eq!(
+            mine.into_iter().collect::<Vec<_>>(),
+            vec![(date(3, 5), 2.0), (date(3, 12), 1.0)]
+        );
+
+        assert!(DataSeries::from_source("git:", None).is_err());
+    }
+
+    #[test]
+    fn test_count_commits_per_repo_author() {
+        // The same person with a work and a personal email, one per repository
+        let work = "me@work.com 2024-03-05
+me@home.org 2024-03-05
+";
+        let home = "me@home.org 2024-03-05
+me@work.com 2024-03-06
+";
+        let mut counts = BTreeMap::new();
+        count_commits(work, "me@work.com", &mut counts);
+        count_commits(home, "me@home.org", &mut counts);
+
+        // Commits under the other repository's email aren't counted
+        assert_eq!(
+            counts.into_iter().collect::<Vec<_>>(),
+            vec![(date(3, 5), 2.0)]
+        );
+    }
+}
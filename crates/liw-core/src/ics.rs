@@ -0,0 +1,634 @@
+//! iCalendar (.ics) import
+//!
+//! Reads events exported from calendar apps so vacations, conferences and
+//! on-call rotations can be marked in the grid. Only dates matter for a week
+//! grid, so times and time zones are dropped and each event covers the days
+//! it touches. Recurring events support `FREQ`, `INTERVAL`, `COUNT`, `UNTIL`
+//! and `BYDAY` for weekly rules, along with `EXDATE` and moved occurrences.
+//! Events outside that subset, such as hourly rules, `BYMONTHDAY` or "second
+//! Tuesday of the month", are skipped and listed in [`Calendar::warnings`]
+//! rather than failing the import.
+
+use anyhow::{Context, Result};
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use std::fs;
+use std::path::Path;
+
+/// How often a recurring event repeats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// A parsed `RRULE`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recurrence {
+    pub frequency: Frequency,
+    /// Repeat every `interval` days, weeks, months or years
+    pub interval: u32,
+    /// Total number of occurrences, including the first
+    pub count: Option<u32>,
+    /// Last day an occurrence may start
+    pub until: Option<NaiveDate>,
+    /// Days of the week for weekly rules; the first day's weekday when empty
+    pub weekdays: Vec<Weekday>,
+}
+
+/// A calendar event, reduced to whole days
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarEvent {
+    /// Unique ID linking moved occurrences to their recurring event
+    pub uid: Option<String>,
+    pub summary: String,
+    pub categories: Vec<String>,
+    /// First day of the (first) occurrence
+    pub start: NaiveDate,
+    /// Last day of the (first) occurrence (inclusive)
+    pub end: NaiveDate,
+    pub recurrence: Option<Recurrence>,
+    /// Start days of occurrences that were removed or moved
+    pub exceptions: Vec<NaiveDate>,
+}
+
+/// Events read from one .ics file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Calendar {
+    /// Calendar name (`X-WR-CALNAME`), or the file name
+    pub name: String,
+    pub events: Vec<CalendarEvent>,
+    /// Events that were skipped and why, for the caller to report
+    pub warnings: Vec<String>,
+}
+
+impl Calendar {
+    /// Load an .ics file, named after the file when it has no name of its own
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read calendar: {:?}", path))?;
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Self::parse(&name, &content).with_context(|| format!("Invalid calendar: {:?}", path))
+    }
+
+    /// Parse iCalendar text
+    pub fn parse(name: &str, content: &str) -> Result<Self> {
+        let mut calendar = Calendar {
+            name: name.to_string(),
+            events: Vec::new(),
+            warnings: Vec::new(),
+        };
+        let mut moved: Vec<(String, NaiveDate)> = Vec::new();
+        let mut event: Option<EventBuilder> = None;
+        let mut found_calendar = false;
+
+        for line in unfold(content) {
+            let Some((name, value)) = split_line(&line) else {
+                continue;
+            };
+            match (name.as_str(), event.as_mut()) {
+                ("BEGIN", None) if value.eq_ignore_ascii_case("VCALENDAR") => {
+                    found_calendar = true;
+                }
+                ("BEGIN", None) if value.eq_ignore_ascii_case("VEVENT") => {
+                    event = Some(EventBuilder::default());
+                }
+                ("END", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => {
+                    let builder = event.take().unwrap();
+                    if let Some(reason) = builder.invalid {
+                        calendar
+                            .warnings
+                            .push(format!("Skipped event {:?}: {}", builder.summary, reason));
+                        continue;
+                    }
+                    if let (Some(uid), Some(date)) = (&builder.uid, builder.recurrence_id) {
+                        moved.push((uid.clone(), date));
+                    }
+                    calendar.events.extend(builder.build());
+                }
+                ("X-WR-CALNAME", None) if !value.is_empty() => {
+                    calendar.name = unescape(value);
+                }
+                (_, Some(builder)) => {
+                    // A property that can't be read costs only its own event
+                    if let Err(err) = builder.set(&name, value) {
+                        builder.invalid.get_or_insert(format!("{:#}", err));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if !found_calendar {
+            anyhow::bail!("Not an iCalendar file: no BEGIN:VCALENDAR");
+        }
+
+        // A moved occurrence replaces the one it was generated as
+        for (uid, date) in moved {
+            for event in &mut calendar.events {
+                if event.recurrence.is_some() && event.uid.as_deref() == Some(uid.as_str()) {
+                    event.exceptions.push(date);
+                }
+            }
+        }
+
+        Ok(calendar)
+    }
+}
+
+impl CalendarEvent {
+    /// First and last day of each occurrence that overlaps `from..=to`
+    pub fn occurrences(&self, from: NaiveDate, to: NaiveDate) -> Vec<(NaiveDate, NaiveDate)> {
+        let length = self.end - self.start;
+        let Some(ref rule) = self.recurrence else {
+            return if self.start <= to && self.end >= from {
+                vec![(self.start, self.end)]
+            } else {
+                Vec::new()
+            };
+        };
+
+        let interval = rule.interval.max(1) as i64;
+        let mut result = Vec::new();
+        let mut generated = 0;
+
+        for period in 0.. {
+            let step = period * interval;
+            let candidates: Vec<NaiveDate> = match rule.frequency {
+                Frequency::Daily => vec![self.start + Duration::days(step)],
+                Frequency::Weekly => {
+                    let monday = self.start
+                        - Duration::days(self.start.weekday().num_days_from_monday() as i64)
+                        + Duration::weeks(step);
+                    if monday > to {
+                        break;
+                    }
+                    let mut days: Vec<NaiveDate> = match rule.weekdays.as_slice() {
+                        [] => vec![self.start + Duration::weeks(step)],
+                        weekdays => weekdays
+                            .iter()
+                            .map(|d| monday + Duration::days(d.num_days_from_monday() as i64))
+                            .filter(|&d| d >= self.start)
+                            .collect(),
+                    };
+                    days.sort();
+                    days
+                }
+                Frequency::Monthly | Frequency::Yearly => {
+                    let months = match rule.frequency {
+                        Frequency::Monthly => step,
+                        _ => step * 12,
+                    };
+                    let index = self.start.year() as i64 * 12 + self.start.month0() as i64 + months;
+                    let (year, month) =
+                        (index.div_euclid(12) as i32, index.rem_euclid(12) as u32 + 1);
+                    match NaiveDate::from_ymd_opt(year, month, 1) {
+                        Some(first) if first <= to => {}
+                        _ => break,
+                    }
+                    // Months without the day (like February 30th) are skipped
+                    NaiveDate::from_ymd_opt(year, month, self.start.day())
+                        .into_iter()
+                        .collect()
+                }
+            };
+
+            for start in candidates {
+                if start > to
+                    || rule.until.is_some_and(|until| start > until)
+                    || rule.count.is_some_and(|count| generated >= count)
+                {
+                    return result;
+                }
+                generated += 1;
+                let end = start + length;
+                if end >= from && !self.exceptions.contains(&start) {
+                    result.push((start, end));
+                }
+            }
+        }
+
+        result
+    }
+}
+
+/// Properties collected between `BEGIN:VEVENT` and `END:VEVENT`
+#[derive(Default)]
+struct EventBuilder {
+    uid: Option<String>,
+    summary: String,
+    categories: Vec<String>,
+    start: Option<(NaiveDate, bool)>,
+    end: Option<(NaiveDate, bool)>,
+    duration_days: Option<i64>,
+    recurrence: Option<Recurrence>,
+    recurrence_id: Option<NaiveDate>,
+    exceptions: Vec<NaiveDate>,
+    cancelled: bool,
+    /// Why the event can't be imported, if it can't
+    invalid: Option<String>,
+}
+
+impl EventBuilder {
+    fn set(&mut self, name: &str, value: &str) -> Result<()> {
+        match name {
+            "UID" => self.uid = Some(value.to_string()),
+            "SUMMARY" => self.summary = unescape(value),
+            "CATEGORIES" => self
+                .categories
+                .extend(split_list(value).filter(|c| !c.is_empty())),
+            "STATUS" => self.cancelled = value.eq_ignore_ascii_case("CANCELLED"),
+            "DTSTART" => self.start = Some(parse_date_time(value)?),
+            "DTEND" => self.end = Some(parse_date_time(value)?),
+            "DURATION" => self.duration_days = Some(parse_duration_days(value)?),
+            "RRULE" => self.recurrence = Some(parse_rrule(value)?),
+            "RECURRENCE-ID" => self.recurrence_id = Some(parse_date_time(value)?.0),
+            "EXDATE" => {
+                for date in value.split(',') {
+                    self.exceptions.push(parse_date_time(date)?.0);
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn build(self) -> Option<CalendarEvent> {
+        let (start, _) = self.start?;
+        if self.cancelled {
+            return None;
+        }
+
+        // All-day ends and ends at midnight are exclusive
+        let end = match (self.end, self.duration_days) {
+            (Some((end, true)), _) => end,
+            (Some((end, false)), _) => end.pred_opt().unwrap_or(end),
+            (None, Some(days)) => start + Duration::days(days - 1),
+            (None, None) => start,
+        };
+
+        Some(CalendarEvent {
+            uid: self.uid,
+            summary: self.summary,
+            categories: self.categories,
+            start,
+            end: end.max(start),
+            recurrence: self.recurrence,
+            exceptions: self.exceptions,
+        })
+    }
+}
+
+/// Join folded lines (continuations start with a space or tab)
+fn unfold(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.trim_end().to_string()),
+        }
+    }
+    lines
+}
+
+/// Split a content line into its uppercased name and its value, dropping
+/// parameters such as `TZID` or `VALUE=DATE`
+fn split_line(line: &str) -> Option<(String, &str)> {
+    let (head, value) = line.split_once(':')?;
+    let name = head.split(';').next().unwrap_or(head);
+    Some((name.trim().to_ascii_uppercase(), value.trim()))
+}
+
+/// Parse a `DATE` (`20240304`) or `DATE-TIME` (`20240304T090000Z`) value
+/// into its day and whether it has a time of day other than midnight
+fn parse_date_time(value: &str) -> Result<(NaiveDate, bool)> {
+    let value = value.trim();
+    let date = value
+        .get(..8)
+        .and_then(|d| NaiveDate::parse_from_str(d, "%Y%m%d").ok())
+        .with_context(|| format!("Invalid date: {:?}", value))?;
+    let has_time = value
+        .get(9..15)
+        .is_some_and(|time| time.bytes().any(|b| b != b'0'));
+    Ok((date, has_time))
+}
+
+/// Whole days covered by a `DURATION` like `P3D`, `P2W` or `PT1H30M`
+fn parse_duration_days(value: &str) -> Result<i64> {
+    let invalid = || format!("Invalid duration: {:?}", value);
+    let rest = value.trim().trim_start_matches('+');
+    let rest = rest.strip_prefix('P').with_context(invalid)?;
+    let date_part = rest.split('T').next().unwrap_or("");
+    let mut days = 0;
+    let mut number = String::new();
+    for c in date_part.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'W' | 'D' => {
+                let n: i64 = number.parse().with_context(invalid)?;
+                days += if c == 'W' { n * 7 } else { n };
+                number.clear();
+            }
+            _ => anyhow::bail!(invalid()),
+        }
+    }
+    // An event shorter than a day still covers the day it starts on
+    Ok(days.max(1))
+}
+
+/// Parse the parts of an `RRULE` that matter for whole days
+fn parse_rrule(value: &str) -> Result<Recurrence> {
+    let mut frequency = None;
+    let mut rule = Recurrence {
+        frequency: Frequency::Daily,
+        interval: 1,
+        count: None,
+        until: None,
+        weekdays: Vec::new(),
+    };
+
+    for part in value.split(';') {
+        let Some((key, value)) = part.split_once('=') else {
+            continue;
+        };
+        match key.to_ascii_uppercase().as_str() {
+            "FREQ" => {
+                frequency = Some(match value.to_ascii_uppercase().as_str() {
+                    "DAILY" => Frequency::Daily,
+                    "WEEKLY" => Frequency::Weekly,
+                    "MONTHLY" => Frequency::Monthly,
+                    "YEARLY" => Frequency::Yearly,
+                    other => anyhow::bail!("Unsupported recurrence: FREQ={}", other),
+                })
+            }
+            "INTERVAL" => {
+                rule.interval = value
+                    .parse()
+                    .with_context(|| format!("Invalid INTERVAL: {:?}", value))?
+            }
+            "COUNT" => {
+                rule.count = Some(
+                    value
+                        .parse()
+                        .with_context(|| format!("Invalid COUNT: {:?}", value))?,
+                )
+            }
+            "UNTIL" => rule.until = Some(parse_date_time(value)?.0),
+            "BYDAY" => {
+                for day in value.split(',') {
+                    // Weekly rules have no ordinals like the "2" in 2TU; drop stray ones
+                    let code = day
+                        .trim_start_matches(|c: char| c == '+' || c == '-' || c.is_ascii_digit());
+                    let weekday = match code.to_ascii_uppercase().as_str() {
+                        "MO" => Weekday::Mon,
+                        "TU" => Weekday::Tue,
+                        "WE" => Weekday::Wed,
+                        "TH" => Weekday::Thu,
+                        "FR" => Weekday::Fri,
+                        "SA" => Weekday::Sat,
+                        "SU" => Weekday::Sun,
+                        _ => anyhow::bail!("Invalid BYDAY: {:?}", day),
+                    };
+                    rule.weekdays.push(weekday);
+                }
+            }
+            // Times within a day don't change which days an event covers
+            "BYHOUR" | "BYMINUTE" | "BYSECOND" => {}
+            other if other.starts_with("BY") => {
+                anyhow::bail!("Unsupported recurrence: {}", part)
+            }
+            _ => {}
+        }
+    }
+
+    rule.frequency = frequency.context("Recurrence rule without FREQ")?;
+    if rule.frequency != Frequency::Weekly && !rule.weekdays.is_empty() {
+        anyhow::bail!(
+            "Unsupported recurrence: BYDAY in a {:?} rule",
+            rule.frequency
+        );
+    }
+    Ok(rule)
+}
+
+/// Undo text escaping (`\,`, `\;`, `\n`, `\\`)
+fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n' | 'N') => out.push(' '),
+                Some(next) => out.push(next),
+                None => {}
+            },
+            c => out.push(c),
+        }
+    }
+    out.trim().to_string()
+}
+
+/// Split a comma-separated text list, keeping escaped commas
+fn split_list(value: &str) -> impl Iterator<Item = String> + '_ {
+    let mut items = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+    for (i, c) in value.char_indices() {
+        match c {
+            '\\' if !escaped => escaped = true,
+            ',' if !escaped => {
+                items.push(&value[start..i]);
+                start = i + 1;
+            }
+            _ => escaped = false,
+        }
+    }
+    items.push(&value[start..]);
+    items.into_iter().map(unescape)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+    }
+
+    const CALENDAR: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+X-WR-CALNAME:Work\r
+BEGIN:VEVENT\r
+UID:trip\r
+SUMMARY:Trip to Lisbon\\, Porto\r
+CATEGORIES:Vacation\r
+DTSTART;VALUE=DATE:20240304\r
+DTEND;VALUE=DATE:20240316\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:oncall\r
+SUMMARY:On-call rotation with a long\r
+  folded summary\r
+CATEGORIES:On-call,Work\r
+DTSTART;TZID=Europe/Berlin:20240101T090000\r
+DTEND;TZID=Europe/Berlin:20240108T090000\r
+RRULE:FREQ=WEEKLY;INTERVAL=4;COUNT=4\r
+EXDATE;TZID=Europe/Berlin:20240129T090000\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:oncall\r
+RECURRENCE-ID;TZID=Europe/Berlin:20240226T090000\r
+SUMMARY:On-call rotation (swapped)\r
+CATEGORIES:On-call\r
+DTSTART;TZID=Europe/Berlin:20240304T090000\r
+DTEND;TZID=Europe/Berlin:20240311T090000\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:Cancelled offsite\r
+STATUS:CANCELLED\r
+DTSTART;VALUE=DATE:20240401\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    #[test]
+    fn test_parse_calendar() {
+        let calendar = Calendar::parse("work", CALENDAR).unwrap();
+        assert_eq!(calendar.name, "Work");
+        assert_eq!(calendar.events.len(), 3);
+
+        let trip = &calendar.events[0];
+        assert_eq!(trip.summary, "Trip to Lisbon, Porto");
+        assert_eq!(trip.categories, ["Vacation"]);
+        // All-day end dates are exclusive
+        assert_eq!((trip.start, trip.end), (date(3, 4), date(3, 15)));
+
+        let on_call = &calendar.events[1];
+        assert_eq!(
+            on_call.summary,
+            "On-call rotation with a long folded summary"
+        );
+        assert_eq!(on_call.categories, ["On-call", "Work"]);
+        assert_eq!(on_call.exceptions, [date(1, 29), date(2, 26)]);
+
+        assert!(Calendar::parse("x", "BEGIN:VEVENT\nEND:VEVENT").is_err());
+    }
+
+    #[test]
+    fn test_recurring_occurrences() {
+        let calendar = Calendar::parse("work", CALENDAR).unwrap();
+        let on_call = &calendar.events[1];
+        // Every four weeks, four times, less the excluded and moved ones
+        assert_eq!(
+            on_call.occurrences(date(1, 1), date(12, 31)),
+            [(date(1, 1), date(1, 8)), (date(3, 25), date(4, 1))]
+        );
+        assert_eq!(on_call.occurrences(date(2, 1), date(3, 1)), []);
+
+        let weekly = |rrule: &str| CalendarEvent {
+            uid: None,
+            summary: "Standup".to_string(),
+            categories: Vec::new(),
+            start: date(3, 5),
+            end: date(3, 5),
+            recurrence: Some(parse_rrule(rrule).unwrap()),
+            exceptions: Vec::new(),
+        };
+        let days = weekly("FREQ=WEEKLY;BYDAY=MO,TU,TH;UNTIL=20240314T000000Z")
+            .occurrences(date(1, 1), date(12, 31));
+        let starts: Vec<_> = days.iter().map(|&(start, _)| start).collect();
+        assert_eq!(
+            starts,
+            [
+                date(3, 5),
+                date(3, 7),
+                date(3, 11),
+                date(3, 12),
+                date(3, 14)
+            ]
+        );
+
+        let monthly = CalendarEvent {
+            start: date(1, 31),
+            end: date(1, 31),
+            ..weekly("FREQ=MONTHLY")
+        };
+        let months: Vec<_> = monthly
+            .occurrences(date(1, 1), date(6, 30))
+            .iter()
+            .map(|&(start, _)| start.month())
+            .collect();
+        assert_eq!(months, [1, 3, 5]);
+        assert!(parse_rrule("INTERVAL=2").is_err());
+        assert!(parse_rrule("FREQ=MONTHLY;BYDAY=2TU").is_err());
+        assert!(parse_rrule("FREQ=WEEKLY;BYDAY=MO;BYHOUR=9").is_ok());
+    }
+
+    #[test]
+    fn test_skip_unsupported_events() {
+        let calendar = Calendar::parse(
+            "work",
+            "BEGIN:VCALENDAR
+BEGIN:VEVENT
+SUMMARY:Heartbeat
+DTSTART:20240304T090000
+RRULE:FREQ=HOURLY;COUNT=8
+END:VEVENT
+BEGIN:VEVENT
+SUMMARY:Board meeting
+DTSTART:20240312T090000
+RRULE:FREQ=MONTHLY;BYDAY=2TU
+END:VEVENT
+BEGIN:VEVENT
+SUMMARY:Garbled
+DTSTART:tomorrow
+END:VEVENT
+BEGIN:VEVENT
+SUMMARY:Retro
+DTSTART;VALUE=DATE:20240315
+END:VEVENT
+END:VCALENDAR",
+        )
+        .unwrap();
+
+        // Only the event we can read survives; the rest don't fail the import
+        let summaries: Vec<_> = calendar.events.iter().map(|e| e.summary.as_str()).collect();
+        assert_eq!(summaries, ["Retro"]);
+        assert_eq!(calendar.warnings.len(), 3);
+        assert!(calendar.warnings[0].contains("Heartbeat"));
+    }
+
+    #[test]
+    fn test_skip_unsupported_by_parts() {
+        let calendar = Calendar::parse(
+            "home",
+            "BEGIN:VCALENDAR
+BEGIN:VEVENT
+SUMMARY:Rent
+DTSTART;VALUE=DATE:20240101
+RRULE:FREQ=MONTHLY;BYMONTHDAY=1,15
+END:VEVENT
+BEGIN:VEVENT
+SUMMARY:Taxes
+DTSTART;VALUE=DATE:20240415
+RRULE:FREQ=YEARLY;BYMONTH=4,10
+END:VEVENT
+BEGIN:VEVENT
+SUMMARY:Payday
+DTSTART;VALUE=DATE:20240131
+RRULE:FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1
+END:VEVENT
+END:VCALENDAR",
+        )
+        .unwrap();
+
+        // Expanding these as plain monthly/yearly rules would put them on the wrong days
+        assert!(calendar.events.is_empty());
+        assert_eq!(calendar.warnings.len(), 3);
+        assert!(calendar.warnings[0].contains("BYMONTHDAY=1,15"));
+    }
+}
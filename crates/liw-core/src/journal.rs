@@ -0,0 +1,173 @@
+//! Weekly journal storage
+//!
+//! Short notes and 1-5 ratings attached to weeks, kept in `journal.toml`
+//! next to the config file. Entries are keyed by the first day of their week.
+
+use crate::config::Config;
+use crate::modes::{Granularity, WeekStart};
+use anyhow::{Context, Result};
+use chrono::{NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// A note, and optionally a rating, for one week
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JournalEntry {
+    /// First day of the week this entry belongs to
+    pub week: NaiveDate,
+    /// Free-form note
+    pub note: String,
+    /// How the week went, from 1 (bad) to 5 (great)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rating: Option<u8>,
+}
+
+/// All journal entries, sorted by week
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Journal {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub entries: Vec<JournalEntry>,
+}
+
+impl Journal {
+    /// Get the default journal file path, next to the config file
+    pub fn default_path() -> Result<PathBuf> {
+        Ok(Config::default_path()?.with_file_name("journal.toml"))
+    }
+
+    /// Load the journal from the default path
+    pub fn load() -> Result<Self> {
+        let path = Self::default_path()?;
+        Self::load_from(&path)
+    }
+
+    /// Load the journal from a specific path, or an empty one if it doesn't exist
+    pub fn load_from(path: &PathBuf) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read journal from {:?}", path))?;
+        toml::from_str(&contents)
+            .with_context(|| format!("Failed to parse journal from {:?}", path))
+    }
+
+    /// Save the journal to the default path
+    pub fn save(&self) -> Result<()> {
+        let path = Self::default_path()?;
+        self.save_to(&path)
+    }
+
+    /// Save the journal to a specific path
+    pub fn save_to(&self, path: &PathBuf) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create journal directory {:?}", parent))?;
+        }
+        let contents = toml::to_string_pretty(self).context("Failed to serialize journal")?;
+        fs::write(path, contents)
+            .with_context(|| format!("Failed to write journal to {:?}", path))?;
+        Ok(())
+    }
+
+    /// Add an entry for the week starting on `week`, after any existing ones
+    pub fn add(&mut self, week: NaiveDate, note: &str, rating: Option<u8>) -> Result<()> {
+        if let Some(rating) = rating.filter(|r| !(1..=5).contains(r)) {
+            anyhow::bail!("Invalid rating: {}. Use 1-5", rating);
+        }
+        self.entries.push(JournalEntry {
+            week,
+            note: note.to_string(),
+            rating,
+        });
+        self.entries.sort_by_key(|e| e.week);
+        Ok(())
+    }
+
+    /// Entries whose week starts between `start` and `end` (inclusive)
+    pub fn entries_between(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> impl Iterator<Item = &JournalEntry> {
+        self.entries
+            .iter()
+            .filter(move |e| start <= e.week && e.week <= end)
+    }
+
+    /// Average rating of the entries between `start` and `end`, rounded
+    pub fn rating_between(&self, start: NaiveDate, end: NaiveDate) -> Option<u8> {
+        let ratings: Vec<u8> = self
+            .entries_between(start, end)
+            .filter_map(|e| e.rating)
+            .collect();
+        if ratings.is_empty() {
+            return None;
+        }
+        let sum: u32 = ratings.iter().map(|&r| r as u32).sum();
+        Some((sum as f64 / ratings.len() as f64).round() as u8)
+    }
+}
+
+/// Parse an ISO week ("2024-W10") or a date ("2024-03-06") into the first day
+/// of the week containing it
+pub fn parse_week(value: &str, week_start: WeekStart) -> Result<NaiveDate> {
+    let date = match value.split_once("-W").or_else(|| value.split_once("-w")) {
+        Some((year, week)) => {
+            let year: i32 = year
+                .parse()
+                .with_context(|| format!("Invalid week: {}. Use YYYY-Www", value))?;
+            let week: u32 = week
+                .parse()
+                .with_context(|| format!("Invalid week: {}. Use YYYY-Www", value))?;
+            NaiveDate::from_isoywd_opt(year, week, Weekday::Mon)
+                .with_context(|| format!("Invalid week: {}. Use YYYY-Www", value))?
+        }
+        None => NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .with_context(|| format!("Invalid week: {}. Use YYYY-Www or YYYY-MM-DD", value))?,
+    };
+    Ok(Granularity::Week.period_start(date, week_start))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_week() {
+        let monday = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        assert_eq!(parse_week("2024-W10", WeekStart::Monday).unwrap(), monday);
+        assert_eq!(parse_week("2024-03-06", WeekStart::Monday).unwrap(), monday);
+        assert_eq!(
+            parse_week("2024-W10", WeekStart::Sunday).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 3, 3).unwrap()
+        );
+        assert!(parse_week("2024-W54", WeekStart::Monday).is_err());
+        assert!(parse_week("last week", WeekStart::Monday).is_err());
+    }
+
+    #[test]
+    fn test_journal_round_trip() {
+        let week = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        let mut journal = Journal::default();
+        journal.add(week(11), "Conference talk", Some(5)).unwrap();
+        journal.add(week(4), "Shipped v1", Some(4)).unwrap();
+        journal.add(week(4), "Flu", Some(1)).unwrap();
+        journal.add(week(11), "Notes only", None).unwrap();
+        assert!(journal.add(week(4), "Too good", Some(6)).is_err());
+
+        let path = std::env::temp_dir().join(format!("liw-journal-{}.toml", std::process::id()));
+        journal.save_to(&path).unwrap();
+        let loaded = Journal::load_from(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.entries.len(), 4);
+        assert_eq!(loaded.entries[0].note, "Shipped v1");
+        assert_eq!(loaded.entries_between(week(4), week(10)).count(), 2);
+        // 4 and 1 average to 2.5, rounded up
+        assert_eq!(loaded.rating_between(week(4), week(10)), Some(3));
+        assert_eq!(loaded.rating_between(week(11), week(17)), Some(5));
+        assert_eq!(loaded.rating_between(week(18), week(24)), None);
+    }
+}
@@ -1,16 +1,30 @@
 //! Life in Weeks Core Library
 //!
 //! This crate provides the core functionality for generating "Life in Weeks" wallpapers.
-//! It includes date calculations, grid rendering, wallpaper setting, and scheduling.
+//! It includes date calculations, life tables, a weekly journal, heatmap data sources, calendar import, grid rendering, wallpaper setting, and scheduling.
 
 pub mod config;
+pub mod data;
+pub mod ics;
+pub mod journal;
+pub mod lifetable;
 pub mod modes;
 pub mod renderer;
 pub mod scheduler;
 pub mod wallpaper;
 
-pub use config::{Config, Theme};
-pub use modes::{Mode, WeekGrid, WeekStatus};
-pub use renderer::render_grid;
+pub use config::{
+    Chapter, Config, CountdownTarget, Grouping, LifeEvent, Person, RecurringItem, Term, TermBreak,
+    Theme,
+};
+pub use data::DataSeries;
+pub use ics::Calendar;
+pub use journal::{Journal, JournalEntry};
+pub use lifetable::{estimate_lifespan, LifespanEstimate, Sex, SurvivalCurve};
+pub use modes::{
+    due_date_from_conception, Granularity, GridOptions, LegendEntry, Mode, ModeParams,
+    WeekCategory, WeekGrid, WeekNumbering, WeekStart, WeekStatus,
+};
+pub use renderer::{render_grid, render_grid_with, CurrentFill, RenderOptions};
 pub use scheduler::{install_schedule, uninstall_schedule};
 pub use wallpaper::set_wallpaper;
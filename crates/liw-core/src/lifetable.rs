@@ -0,0 +1,406 @@
+//! Embedded period life tables and survival curves
+//!
+//! Used to estimate lifespan and to shade future weeks by the probability of
+//! being alive. Each table lists survivors out of 100,000 births at the ages in [`AGES`].
+//! The figures are smoothed Gompertz-Makeham fits to each country's period
+//! life expectancy at birth around 2019. They are good enough to pick a
+//! realistic `lifespan_years`, not for actuarial work.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Ages (in years) at which survivors are tabulated
+pub const AGES: [u32; 24] = [
+    0, 1, 5, 10, 15, 20, 25, 30, 35, 40, 45, 50, 55, 60, 65, 70, 75, 80, 85, 90, 95, 100, 105, 110,
+];
+
+/// Expected years lived beyond the last tabulated age
+const TAIL_YEARS: f64 = 2.0;
+
+/// Sex used to select a column of the life table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Sex {
+    Male,
+    Female,
+}
+
+impl Sex {
+    /// Parse sex from string
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.to_lowercase().as_str() {
+            "male" | "m" => Ok(Sex::Male),
+            "female" | "f" => Ok(Sex::Female),
+            _ => Err(format!("Unknown sex: {}. Options: male, female", value)),
+        }
+    }
+}
+
+/// Period life table for one country
+#[derive(Debug)]
+pub struct LifeTable {
+    /// ISO 3166-1 alpha-2 country code
+    pub code: &'static str,
+    /// Country name
+    pub name: &'static str,
+    /// Male survivors out of 100,000 at each age in `AGES`
+    male: [u32; 24],
+    /// Female survivors out of 100,000 at each age in `AGES`
+    female: [u32; 24],
+}
+
+/// All embedded life tables
+pub static LIFE_TABLES: [LifeTable; 9] = [
+    LifeTable {
+        code: "US",
+        name: "United States",
+        male: [
+            100000, 99587, 99094, 98577, 98039, 97469, 96846, 96142, 95312, 94286, 92962, 91184,
+            88726, 85265, 80371, 73517, 64185, 52125, 37832, 23061, 10721, 3273, 520, 30,
+        ],
+        female: [
+            100000, 99624, 99281, 98954, 98612, 98246, 97843, 97382, 96831, 96141, 95236, 94005,
+            92277, 89805, 86241, 81119, 73886, 64017, 51326, 36481, 21506, 9482, 2663, 371,
+        ],
+    },
+    LifeTable {
+        code: "GB",
+        name: "United Kingdom",
+        male: [
+            100000, 99741, 99478, 99215, 98939, 98641, 98306, 97912, 97425, 96788, 95912, 94658,
+            92811, 90048, 85901, 79746, 70863, 58693, 43413, 26771, 12322, 3544, 478, 19,
+        ],
+        female: [
+            100000, 99760, 99571, 99404, 99227, 99033, 98811, 98546, 98211, 97763, 97135, 96223,
+            94859, 92792, 89644, 84881, 77819, 67735, 54237, 37974, 21426, 8545, 1950, 181,
+        ],
+    },
+    LifeTable {
+        code: "DE",
+        name: "Germany",
+        male: [
+            100000, 99767, 99510, 99246, 98967, 98663, 98321, 97916, 97410, 96743, 95819, 94490,
+            92527, 89586, 85175, 78648, 69286, 56594, 40936, 24352, 10580, 2773, 322, 10,
+        ],
+        female: [
+            100000, 99786, 99605, 99439, 99262, 99070, 98851, 98590, 98261, 97823, 97213, 96327,
+            95006, 93004, 89955, 85338, 78477, 68645, 55402, 39286, 22625, 9324, 2244, 227,
+        ],
+    },
+    LifeTable {
+        code: "FR",
+        name: "France",
+        male: [
+            100000, 99768, 99514, 99254, 98983, 98692, 98369, 97992, 97530, 96928, 96103, 94920,
+            93170, 90530, 86531, 80529, 71762, 59600, 44148, 27156, 12353, 3442, 433, 15,
+        ],
+        female: [
+            100000, 99786, 99608, 99447, 99278, 99098, 98898, 98666, 98383, 98017, 97517, 96800,
+            95737, 94123, 91645, 87841, 82073, 73570, 61654, 46315, 29131, 13731, 4051, 558,
+        ],
+    },
+    LifeTable {
+        code: "JP",
+        name: "Japan",
+        male: [
+            100000, 99862, 99697, 99523, 99339, 99139, 98914, 98646, 98308, 97855, 97212, 96263,
+            94815, 92572, 89081, 83698, 75603, 63995, 48669, 31022, 14779, 4357, 582, 21,
+        ],
+        female: [
+            100000, 99875, 99761, 99653, 99539, 99417, 99280, 99119, 98919, 98653, 98280, 97732,
+            96897, 95598, 93555, 90341, 85336, 77733, 66691, 51838, 34239, 17291, 5610, 877,
+        ],
+    },
+    LifeTable {
+        code: "CA",
+        name: "Canada",
+        male: [
+            100000, 99715, 99445, 99184, 98909, 98613, 98283, 97896, 97419, 96799, 95949, 94737,
+            92955, 90292, 86294, 80350, 71743, 59881, 44845, 28211, 13408, 4060, 595, 27,
+        ],
+        female: [
+            100000, 99734, 99539, 99374, 99199, 99009, 98795, 98541, 98223, 97804, 97223, 96383,
+            95133, 93244, 90366, 86002, 79494, 70110, 57340, 41537, 24755, 10780, 2835, 331,
+        ],
+    },
+    LifeTable {
+        code: "AU",
+        name: "Australia",
+        male: [
+            100000, 99768, 99515, 99259, 98993, 98709, 98398, 98041, 97610, 97060, 96318, 95266,
+            93723, 91406, 87897, 82606, 74794, 63744, 49242, 32427, 16477, 5495, 925, 51,
+        ],
+        female: [
+            100000, 99786, 99608, 99446, 99276, 99093, 98890, 98653, 98362, 97983, 97461, 96709,
+            95590, 93888, 91274, 87264, 81199, 72300, 59935, 44236, 27036, 12163, 3327, 406,
+        ],
+    },
+    LifeTable {
+        code: "IN",
+        name: "India",
+        male: [
+            100000, 98122, 96394, 94781, 93160, 91514, 89823, 88057, 86174, 84117, 81804, 79126,
+            75936, 72045, 67225, 61226, 53831, 44963, 34855, 24240, 14394, 6792, 2294, 476,
+        ],
+        female: [
+            100000, 98239, 96970, 95915, 94833, 93707, 92511, 91213, 89764, 88097, 86117, 83696,
+            80660, 76787, 71808, 65431, 57411, 47681, 36557, 24951, 14378, 6477, 2039, 381,
+        ],
+    },
+    LifeTable {
+        code: "BR",
+        name: "Brazil",
+        male: [
+            100000, 99079, 97908, 96679, 95437, 94168, 92852, 91462, 89956, 88274, 86332, 84008,
+            81134, 77485, 72771, 66660, 58829, 49112, 37739, 25627, 14470, 6205, 1765, 272,
+        ],
+        female: [
+            100000, 99170, 98362, 97585, 96794, 95980, 95128, 94218, 93219, 92088, 90761, 89144,
+            87106, 84463, 80967, 76299, 70089, 61980, 51784, 39750, 26891, 15066, 6371, 1771,
+        ],
+    },
+];
+
+/// Find the life table for a country code or name (case-insensitive)
+pub fn life_table(country: &str) -> Result<&'static LifeTable, String> {
+    LIFE_TABLES
+        .iter()
+        .find(|t| t.code.eq_ignore_ascii_case(country) || t.name.eq_ignore_ascii_case(country))
+        .ok_or_else(|| {
+            let codes: Vec<&str> = LIFE_TABLES.iter().map(|t| t.code).collect();
+            format!(
+                "No life table for country: {}. Options: {}",
+                country,
+                codes.join(", ")
+            )
+        })
+}
+
+impl LifeTable {
+    /// Survival curve for one sex
+    pub fn curve(&self, sex: Sex) -> SurvivalCurve {
+        let column = match sex {
+            Sex::Male => &self.male,
+            Sex::Female => &self.female,
+        };
+        SurvivalCurve {
+            points: AGES
+                .iter()
+                .zip(column)
+                .map(|(&age, &alive)| (age as f64, alive as f64 / 100_000.0))
+                .collect(),
+        }
+    }
+}
+
+/// Share of a cohort alive at each age, from a life table or a CSV file
+#[derive(Debug, Clone, PartialEq)]
+pub struct SurvivalCurve {
+    /// `(age, share alive)` pairs sorted by age, starting at 1.0
+    points: Vec<(f64, f64)>,
+}
+
+impl SurvivalCurve {
+    /// Parse a curve from CSV lines of `age,survivors`
+    ///
+    /// Survivors may be counts (e.g. out of 100,000) or shares; they are
+    /// scaled so the first row is 1.0. A header line and `#` comments are skipped.
+    pub fn parse_csv(content: &str) -> Result<Self, String> {
+        let mut points: Vec<(f64, f64)> = Vec::new();
+
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split(',').map(str::trim);
+            let (Some(age), Some(alive)) = (fields.next(), fields.next()) else {
+                return Err(format!("Line {}: expected age,survivors", number + 1));
+            };
+            let (Ok(age), Ok(alive)) = (age.parse::<f64>(), alive.parse::<f64>()) else {
+                if points.is_empty() {
+                    // Header row
+                    continue;
+                }
+                return Err(format!("Line {}: invalid number in {:?}", number + 1, line));
+            };
+            if points.last().is_some_and(|&(last, _)| age <= last) {
+                return Err(format!("Line {}: ages must increase", number + 1));
+            }
+            if alive < 0.0 {
+                return Err(format!("Line {}: survivors can't be negative", number + 1));
+            }
+            points.push((age, alive));
+        }
+
+        let first = match points.first() {
+            Some(&(_, alive)) if alive > 0.0 => alive,
+            Some(_) => return Err("The first row must have survivors".to_string()),
+            None => return Err("Survival curve has no rows".to_string()),
+        };
+        for point in &mut points {
+            point.1 = (point.1 / first).min(1.0);
+        }
+
+        Ok(Self { points })
+    }
+
+    /// Share of the cohort still alive at `age`, interpolated at a constant
+    /// hazard between tabulated ages
+    pub fn survivors(&self, age: f64) -> f64 {
+        let (first_age, _) = self.points[0];
+        if age <= first_age {
+            return 1.0;
+        }
+        let (last_age, _) = self.points[self.points.len() - 1];
+        if age >= last_age {
+            return 0.0;
+        }
+
+        let i = self.points.partition_point(|&(a, _)| a <= age) - 1;
+        let ((a0, l0), (a1, l1)) = (self.points[i], self.points[i + 1]);
+        let fraction = (age - a0) / (a1 - a0);
+        if l1 > 0.0 {
+            l0 * (l1 / l0).powf(fraction)
+        } else {
+            l0 * (1.0 - fraction)
+        }
+    }
+
+    /// Probability of surviving from `from_age` to `to_age`
+    pub fn survival_probability(&self, from_age: f64, to_age: f64) -> f64 {
+        let alive = self.survivors(from_age);
+        if alive <= 0.0 {
+            return 0.0;
+        }
+        (self.survivors(to_age) / alive).min(1.0)
+    }
+
+    /// Oldest tabulated age
+    pub fn max_age(&self) -> f64 {
+        self.points[self.points.len() - 1].0
+    }
+
+    /// Expected remaining years of life for someone who has reached `age`
+    pub fn remaining_life_expectancy(&self, age: f64) -> f64 {
+        let alive = self.survivors(age);
+        if alive <= 0.0 {
+            return TAIL_YEARS;
+        }
+
+        // Integrate the survival curve from `age` onwards, one tabulated interval at a time
+        let mut years_lived = 0.0;
+        let mut from = age;
+        for &(to, _) in self.points.iter().filter(|&&(a, _)| a > age) {
+            let (l0, l1) = (self.survivors(from), self.survivors(to));
+            years_lived += if l1 <= 0.0 {
+                l0 * (to - from) / 2.0
+            } else if l0 == l1 {
+                l0 * (to - from)
+            } else {
+                (l0 - l1) * (to - from) / (l0 / l1).ln()
+            };
+            from = to;
+        }
+        // The last tabulated age has survivors that `survivors` reports as zero
+        years_lived += self.points[self.points.len() - 1].1 * TAIL_YEARS;
+
+        years_lived / alive
+    }
+}
+
+/// A lifespan estimate for a specific person
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LifespanEstimate {
+    /// Current age in years
+    pub age: f64,
+    /// Expected remaining years of life at the current age
+    pub remaining_years: f64,
+    /// Expected age at death (current age plus remaining years)
+    pub lifespan_years: f64,
+}
+
+/// Estimate lifespan from sex, country and date of birth, conditional on
+/// having survived to `today`
+pub fn estimate_lifespan(
+    sex: Sex,
+    country: &str,
+    dob: NaiveDate,
+    today: NaiveDate,
+) -> Result<LifespanEstimate, String> {
+    if dob > today {
+        return Err(format!("Date of birth {} is in the future", dob));
+    }
+    let table = life_table(country)?;
+    let age = (today - dob).num_days() as f64 / 365.25;
+    let remaining_years = table.curve(sex).remaining_life_expectancy(age);
+
+    Ok(LifespanEstimate {
+        age,
+        remaining_years,
+        lifespan_years: age + remaining_years,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_life_expectancy_at_birth() {
+        let us = life_table("us").unwrap();
+        let male = us.curve(Sex::Male).remaining_life_expectancy(0.0);
+        let female = us.curve(Sex::Female).remaining_life_expectancy(0.0);
+        assert!((male - 76.3).abs() < 0.2, "{}", male);
+        assert!((female - 81.4).abs() < 0.2, "{}", female);
+
+        assert_eq!(life_table("Japan").unwrap().code, "JP");
+        assert!(life_table("Atlantis").is_err());
+    }
+
+    #[test]
+    fn test_estimate_lifespan() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 13).unwrap();
+        let young = estimate_lifespan(
+            Sex::Female,
+            "GB",
+            NaiveDate::from_ymd_opt(1994, 3, 13).unwrap(),
+            today,
+        )
+        .unwrap();
+        let old = estimate_lifespan(
+            Sex::Female,
+            "GB",
+            NaiveDate::from_ymd_opt(1944, 3, 13).unwrap(),
+            today,
+        )
+        .unwrap();
+
+        // Having survived longer raises the expected age at death
+        assert!(old.lifespan_years > young.lifespan_years);
+        assert!(young.remaining_years > old.remaining_years);
+        assert!((young.age - 30.0).abs() < 0.01);
+
+        let curve = life_table("GB").unwrap().curve(Sex::Male);
+        assert_eq!(curve.survival_probability(40.0, 40.0), 1.0);
+        assert!(curve.survival_probability(40.0, 80.0) < 1.0);
+        assert!(estimate_lifespan(Sex::Male, "GB", today.succ_opt().unwrap(), today).is_err());
+    }
+
+    #[test]
+    fn test_parse_survival_csv() {
+        let curve = SurvivalCurve::parse_csv(
+            "age,survivors\n# synthetic cohort\n0,1000\n50,800\n100,100\n",
+        )
+        .unwrap();
+        assert_eq!(curve.survivors(0.0), 1.0);
+        assert!((curve.survivors(50.0) - 0.8).abs() < 1e-9);
+        assert!((curve.survival_probability(50.0, 100.0 - 1e-9) - 0.125).abs() < 1e-6);
+        assert_eq!(curve.survivors(100.0), 0.0);
+        assert_eq!(curve.max_age(), 100.0);
+
+        assert!(SurvivalCurve::parse_csv("").is_err());
+        assert!(SurvivalCurve::parse_csv("0,1\n50,0.5\n40,0.4").is_err());
+        assert!(SurvivalCurve::parse_csv("0,1\n50,lots").is_err());
+    }
+}
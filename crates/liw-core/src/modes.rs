@@ -1,12 +1,30 @@
 //! Date calculation modes for Life in Weeks
 //!
-//! Supports three modes:
+//! Supports thirteen modes:
 //! - Next N months
 //! - Until end of year
+//! - Full current year (January 1 to December 31)
 //! - Life in weeks (DOB to expected lifespan)
+//! - Arbitrary date range
+//! - Countdown to one or more target dates
+//! - Several people's lives on one calendar-aligned grid
+//! - Remaining occurrences of recurring things ("the tail end")
+//! - Countdown to retirement, in working weeks
+//! - Academic term with break and exam weeks
+//! - Fiscal year with one row per quarter
+//! - Sprints from an anchor date up to a release
+//! - A pregnancy by week, followed by the baby's first year
 
-use chrono::{Datelike, Local, NaiveDate};
+use crate::config::{
+    Chapter, Config, CountdownTarget, Grouping, LifeEvent, Person, RecurringItem, Term,
+};
+use crate::data::DataSeries;
+use crate::ics::Calendar;
+use crate::journal::Journal;
+use crate::lifetable::SurvivalCurve;
+use chrono::{Datelike, Local, NaiveDate, Weekday};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// The mode for calculating weeks
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,37 +35,539 @@ pub enum Mode {
     /// Show weeks from now until the end of the current year
     YearEnd,
     /// Show entire life in weeks from DOB to expected lifespan
-    Life { dob: NaiveDate, lifespan_years: u8 },
+    Life {
+        dob: NaiveDate,
+        lifespan_years: u8,
+        /// Start each row on a birthday, so row N is age N
+        #[serde(default)]
+        age_rows: bool,
+    },
+    /// Show an arbitrary span of dates (contract, sabbatical, launch window)
+    Range {
+        start: NaiveDate,
+        end: NaiveDate,
+        title: Option<String>,
+    },
+    /// Count down the weeks until one or more target dates
+    Countdown { targets: Vec<CountdownTarget> },
+    /// Show the whole current year, including elapsed weeks
+    FullYear { month_rows: bool },
+    /// Show several people's lives on one grid, one row per calendar year
+    Family { people: Vec<Person> },
+    /// Count the remaining occurrences of recurring things over a lifetime
+    TailEnd {
+        dob: NaiveDate,
+        lifespan_years: u8,
+        items: Vec<RecurringItem>,
+    },
+    /// Count down the weeks until retirement, separating work weeks from time off
+    Retirement {
+        dob: Option<NaiveDate>,
+        retire_on: NaiveDate,
+        vacation_weeks: u8,
+        holiday_days: u8,
+    },
+    /// Show the current academic term, with break and exam weeks set apart
+    Term { terms: Vec<Term> },
+    /// Show the current fiscal year, one row per quarter
+    Fiscal { start_month: u32 },
+    /// Show weeks grouped into sprints, counted from an anchor date up to a release
+    Sprint {
+        anchor: NaiveDate,
+        release: NaiveDate,
+        sprint_weeks: u8,
+    },
+    /// Show a 40-week pregnancy, then the baby's first year after birth
+    Pregnancy {
+        due: NaiveDate,
+        born: Option<NaiveDate>,
+    },
+}
+
+/// Optional parameters used when parsing a mode by name
+#[derive(Debug, Clone, Default)]
+pub struct ModeParams {
+    /// Date of birth for life mode
+    pub dob: Option<NaiveDate>,
+    /// Expected lifespan in years for life mode
+    pub lifespan: Option<u8>,
+    /// Lay out life mode with one row per year of age
+    pub age_rows: bool,
+    /// Number of months for next-months mode
+    pub months: Option<u8>,
+    /// First day for range mode
+    pub start: Option<NaiveDate>,
+    /// Last day for range mode
+    pub end: Option<NaiveDate>,
+    /// Custom title for range mode
+    pub title: Option<String>,
+    /// Deadlines for countdown mode
+    pub targets: Vec<CountdownTarget>,
+    /// Lay out full-year mode with one row per month
+    pub month_rows: bool,
+    /// People for family mode
+    pub people: Vec<Person>,
+    /// Recurring things for tail-end mode
+    pub recurring: Vec<RecurringItem>,
+    /// Age at retirement for retirement mode, used with `dob`
+    pub retirement_age: Option<u8>,
+    /// Retirement date for retirement mode; wins over `retirement_age`
+    pub retirement_date: Option<NaiveDate>,
+    /// Vacation weeks per year for retirement mode
+    pub vacation_weeks: Option<u8>,
+    /// Public holidays (days) per year for retirement mode
+    pub holiday_days: Option<u8>,
+    /// Academic terms for term mode
+    pub terms: Vec<Term>,
+    /// First month (1-12) of the fiscal year for fiscal mode
+    pub fiscal_start_month: Option<u32>,
+    /// First day of sprint 1 for sprint mode
+    pub sprint_anchor: Option<NaiveDate>,
+    /// Release date that ends sprint mode
+    pub release: Option<NaiveDate>,
+    /// Length of a sprint in weeks for sprint mode
+    pub sprint_weeks: Option<u8>,
+    /// Due date for pregnancy mode
+    pub due: Option<NaiveDate>,
+    /// Conception date for pregnancy mode, used when no due date is given
+    pub conceived: Option<NaiveDate>,
+    /// Birth date for pregnancy mode, when it differs from the due date
+    pub born: Option<NaiveDate>,
 }
 
 impl Mode {
+    /// Canonical name of the mode, as accepted by `from_str_with_params`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mode::NextMonths { .. } => "next-months",
+            Mode::YearEnd => "year-end",
+            Mode::Life { .. } => "life",
+            Mode::Range { .. } => "range",
+            Mode::Countdown { .. } => "countdown",
+            Mode::FullYear { .. } => "full-year",
+            Mode::Family { .. } => "family",
+            Mode::TailEnd { .. } => "tail-end",
+            Mode::Retirement { .. } => "retirement",
+            Mode::Term { .. } => "term",
+            Mode::Fiscal { .. } => "fiscal",
+            Mode::Sprint { .. } => "sprint",
+            Mode::Pregnancy { .. } => "pregnancy",
+        }
+    }
+
     /// Parse mode from string with optional parameters
-    pub fn from_str_with_params(
-        mode: &str,
-        dob: Option<NaiveDate>,
-        lifespan: Option<u8>,
-        months: Option<u8>,
-    ) -> Result<Self, String> {
+    pub fn from_str_with_params(mode: &str, params: &ModeParams) -> Result<Self, String> {
         match mode.to_lowercase().as_str() {
             "next-months" | "next_months" | "months" => Ok(Mode::NextMonths {
-                months: months.unwrap_or(6),
+                months: params.months.unwrap_or(6),
             }),
             "year-end" | "year_end" | "year" => Ok(Mode::YearEnd),
             "life" | "life-weeks" | "life_weeks" => {
-                let dob = dob.ok_or("DOB is required for life mode")?;
+                let dob = params.dob.ok_or("DOB is required for life mode")?;
                 Ok(Mode::Life {
                     dob,
-                    lifespan_years: lifespan.unwrap_or(80),
+                    lifespan_years: params.lifespan.unwrap_or(80),
+                    age_rows: params.age_rows,
+                })
+            }
+            "range" | "date-range" | "date_range" => {
+                let start = params
+                    .start
+                    .ok_or("Start date is required for range mode")?;
+                let end = params.end.ok_or("End date is required for range mode")?;
+                if end < start {
+                    return Err(format!("Range end {} is before start {}", end, start));
+                }
+                Ok(Mode::Range {
+                    start,
+                    end,
+                    title: params.title.clone(),
+                })
+            }
+            "countdown" | "deadline" | "deadlines" => {
+                if params.targets.is_empty() {
+                    return Err(
+                        "At least one target is required for countdown mode (see 'liw target add')"
+                            .to_string(),
+                    );
+                }
+                Ok(Mode::Countdown {
+                    targets: params.targets.clone(),
+                })
+            }
+            "full-year" | "full_year" | "calendar-year" | "calendar_year" => Ok(Mode::FullYear {
+                month_rows: params.month_rows,
+            }),
+            "family" | "people" | "partner" => {
+                if params.people.is_empty() {
+                    return Err(
+                        "At least one person is required for family mode (see 'liw person add')"
+                            .to_string(),
+                    );
+                }
+                // Fill in the default lifespan so the mode is self-contained
+                let lifespan = params.lifespan.unwrap_or(80);
+                let people = params
+                    .people
+                    .iter()
+                    .map(|p| Person {
+                        lifespan_years: Some(p.lifespan_years.unwrap_or(lifespan)),
+                        ..p.clone()
+                    })
+                    .collect();
+                Ok(Mode::Family { people })
+            }
+            "tail-end" | "tail_end" | "tail" | "occurrences" => {
+                let dob = params.dob.ok_or("DOB is required for tail-end mode")?;
+                if params.recurring.is_empty() {
+                    return Err(
+                        "At least one recurring item is required for tail-end mode (add [[recurring]] to config.toml)"
+                            .to_string(),
+                    );
+                }
+                if let Some(item) = params.recurring.iter().find(|i| i.per_year <= 0.0) {
+                    return Err(format!(
+                        "{} must happen a positive number of times per year",
+                        item.name
+                    ));
+                }
+                Ok(Mode::TailEnd {
+                    dob,
+                    lifespan_years: params.lifespan.unwrap_or(80),
+                    items: params.recurring.clone(),
+                })
+            }
+            "retirement" | "retire" => {
+                let retire_on = match (params.retirement_date, params.dob) {
+                    (Some(date), _) => date,
+                    (None, Some(dob)) => add_years(dob, params.retirement_age.unwrap_or(65) as i32),
+                    (None, None) => {
+                        return Err(
+                            "Retirement date or DOB is required for retirement mode".to_string()
+                        )
+                    }
+                };
+                Ok(Mode::Retirement {
+                    dob: params.dob,
+                    retire_on,
+                    vacation_weeks: params.vacation_weeks.unwrap_or(0),
+                    holiday_days: params.holiday_days.unwrap_or(0),
+                })
+            }
+            "term" | "terms" | "semester" | "academic" => {
+                if params.terms.is_empty() {
+                    return Err(
+                        "At least one term is required for term mode (add [[terms]] to config.toml)"
+                            .to_string(),
+                    );
+                }
+                if let Some(term) = params.terms.iter().find(|t| t.end < t.start) {
+                    return Err(format!(
+                        "Term {} ends {} before it starts {}",
+                        term.name, term.end, term.start
+                    ));
+                }
+                Ok(Mode::Term {
+                    terms: params.terms.clone(),
+                })
+            }
+            "fiscal" | "fiscal-year" | "fiscal_year" | "quarters" => {
+                let start_month = params.fiscal_start_month.unwrap_or(1);
+                if !(1..=12).contains(&start_month) {
+                    return Err(format!(
+                        "Invalid fiscal start month: {}. Use 1-12",
+                        start_month
+                    ));
+                }
+                Ok(Mode::Fiscal { start_month })
+            }
+            "sprint" | "sprints" => {
+                let anchor = params
+                    .sprint_anchor
+                    .ok_or("Sprint anchor date is required for sprint mode")?;
+                let release = params
+                    .release
+                    .ok_or("Release date is required for sprint mode")?;
+                if release < anchor {
+                    return Err(format!(
+                        "Release {} is before sprint anchor {}",
+                        release, anchor
+                    ));
+                }
+                let sprint_weeks = params.sprint_weeks.unwrap_or(2);
+                if sprint_weeks == 0 {
+                    return Err("Sprints must be at least one week long".to_string());
+                }
+                Ok(Mode::Sprint {
+                    anchor,
+                    release,
+                    sprint_weeks,
+                })
+            }
+            "pregnancy" | "baby" | "newborn" => {
+                let due = params
+                    .due
+                    .or(params.conceived.map(due_date_from_conception))
+                    .ok_or("Due date or conception date is required for pregnancy mode")?;
+                Ok(Mode::Pregnancy {
+                    due,
+                    born: params.born,
                 })
             }
             _ => Err(format!(
-                "Unknown mode: {}. Options: next-months, year-end, life",
+                "Unknown mode: {}. Options: next-months, year-end, full-year, life, range, countdown, family, tail-end, retirement, term, fiscal, sprint, pregnancy",
                 mode
             )),
         }
     }
 }
 
+/// Time unit represented by a single grid cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Granularity {
+    /// One cell per day
+    Day,
+    /// One cell per week
+    #[default]
+    Week,
+    /// One cell per calendar month
+    Month,
+    /// One cell per calendar year
+    Year,
+}
+
+impl Granularity {
+    /// Parse granularity from string
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.to_lowercase().as_str() {
+            "day" | "days" | "daily" => Ok(Granularity::Day),
+            "week" | "weeks" | "weekly" => Ok(Granularity::Week),
+            "month" | "months" | "monthly" => Ok(Granularity::Month),
+            "year" | "years" | "yearly" => Ok(Granularity::Year),
+            _ => Err(format!(
+                "Unknown granularity: {}. Options: day, week, month, year",
+                value
+            )),
+        }
+    }
+
+    /// Singular unit name used in titles and subtitles ("week", "month", ...)
+    pub fn singular(self) -> &'static str {
+        match self {
+            Granularity::Day => "day",
+            Granularity::Week => "week",
+            Granularity::Month => "month",
+            Granularity::Year => "year",
+        }
+    }
+
+    /// Plural unit name used in titles and subtitles ("weeks", "months", ...)
+    pub fn plural(self) -> &'static str {
+        match self {
+            Granularity::Day => "days",
+            Granularity::Week => "weeks",
+            Granularity::Month => "months",
+            Granularity::Year => "years",
+        }
+    }
+
+    /// Approximate number of cells per year
+    pub fn per_year(self) -> usize {
+        match self {
+            Granularity::Day => 365,
+            Granularity::Week => 52,
+            Granularity::Month => 12,
+            Granularity::Year => 1,
+        }
+    }
+
+    /// First day of the period containing `date`
+    pub fn period_start(self, date: NaiveDate, first_day: WeekStart) -> NaiveDate {
+        match self {
+            Granularity::Day => date,
+            Granularity::Week => week_start(date, first_day),
+            Granularity::Month => date.with_day(1).unwrap(),
+            Granularity::Year => NaiveDate::from_ymd_opt(date.year(), 1, 1).unwrap(),
+        }
+    }
+
+    /// First day of the period following the one starting at `start`
+    fn next_period(self, start: NaiveDate) -> NaiveDate {
+        match self {
+            Granularity::Day => start + chrono::Duration::days(1),
+            Granularity::Week => start + chrono::Duration::days(7),
+            Granularity::Month => add_months(start, 1),
+            Granularity::Year => add_years(start, 1),
+        }
+    }
+
+    /// Number of whole periods from the period containing `from` to the one containing `to`
+    pub fn periods_between(self, from: NaiveDate, to: NaiveDate, first_day: WeekStart) -> i64 {
+        match self {
+            Granularity::Day => (to - from).num_days(),
+            Granularity::Week => {
+                (week_start(to, first_day) - week_start(from, first_day)).num_weeks()
+            }
+            Granularity::Month => {
+                (to.year() as i64 * 12 + to.month() as i64)
+                    - (from.year() as i64 * 12 + from.month() as i64)
+            }
+            Granularity::Year => (to.year() - from.year()) as i64,
+        }
+    }
+}
+
+/// Day on which each week begins
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WeekStart {
+    /// ISO and European convention
+    #[default]
+    Monday,
+    /// US convention
+    Sunday,
+    /// Common in parts of the Middle East
+    Saturday,
+}
+
+impl WeekStart {
+    /// Parse week start day from string
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.to_lowercase().as_str() {
+            "monday" | "mon" => Ok(WeekStart::Monday),
+            "sunday" | "sun" => Ok(WeekStart::Sunday),
+            "saturday" | "sat" => Ok(WeekStart::Saturday),
+            _ => Err(format!(
+                "Unknown week start: {}. Options: monday, sunday, saturday",
+                value
+            )),
+        }
+    }
+
+    /// The corresponding chrono weekday
+    pub fn weekday(self) -> Weekday {
+        match self {
+            WeekStart::Monday => Weekday::Mon,
+            WeekStart::Sunday => Weekday::Sun,
+            WeekStart::Saturday => Weekday::Sat,
+        }
+    }
+
+    /// Number of days from the start of the week to `date`'s weekday (0-6)
+    fn days_into_week(self, date: NaiveDate) -> u32 {
+        (date.weekday().num_days_from_monday() + 7 - self.weekday().num_days_from_monday()) % 7
+    }
+}
+
+/// Scheme used to number weeks within a year
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WeekNumbering {
+    /// ISO 8601: week 1 contains the year's first Thursday
+    #[default]
+    Iso,
+    /// US: week 1 contains January 1
+    Us,
+    /// Simple: January 1-7 is week 1, January 8-14 is week 2, and so on
+    Simple,
+}
+
+impl WeekNumbering {
+    /// Parse week numbering scheme from string
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.to_lowercase().as_str() {
+            "iso" | "iso8601" | "iso-8601" => Ok(WeekNumbering::Iso),
+            "us" | "usa" => Ok(WeekNumbering::Us),
+            "simple" | "plain" => Ok(WeekNumbering::Simple),
+            _ => Err(format!(
+                "Unknown week numbering: {}. Options: iso, us, simple",
+                value
+            )),
+        }
+    }
+
+    /// Number (1-53) of the week beginning on `start`
+    ///
+    /// US weeks begin on `first_day`, so a week straddling New Year is
+    /// week 1 of the new year.
+    pub fn week_number(self, start: NaiveDate, first_day: WeekStart) -> u32 {
+        match self {
+            // The midpoint of a week always falls in the ISO week holding most of its days
+            WeekNumbering::Iso => (start + chrono::Duration::days(3)).iso_week().week(),
+            WeekNumbering::Us => {
+                let last = start + chrono::Duration::days(6);
+                let jan1 = NaiveDate::from_ymd_opt(last.year(), 1, 1).unwrap();
+                (last.ordinal0() + first_day.days_into_week(jan1)) / 7 + 1
+            }
+            WeekNumbering::Simple => start.ordinal0() / 7 + 1,
+        }
+    }
+
+    /// Number of weeks (52 or 53) this scheme gives `year`
+    pub fn weeks_in_year(self, year: i32, first_day: WeekStart) -> u32 {
+        // Walk from midsummer to the week numbered 1 of the next year
+        let midsummer = NaiveDate::from_ymd_opt(year, 7, 1).unwrap();
+        let mut start = Granularity::Week.period_start(midsummer, first_day);
+        let mut last = self.week_number(start, first_day);
+        loop {
+            start += chrono::Duration::weeks(1);
+            match self.week_number(start, first_day) {
+                1 => return last,
+                number => last = number,
+            }
+        }
+    }
+}
+
+/// Calendar settings shared by every mode
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GridOptions {
+    /// Time unit of a single cell
+    pub granularity: Granularity,
+    /// Day on which weeks begin
+    pub week_start: WeekStart,
+    /// Scheme used for `Week::week_of_year`
+    pub week_numbering: WeekNumbering,
+    /// Reference date to render the grid as of; `None` means today
+    pub as_of: Option<NaiveDate>,
+}
+
+impl GridOptions {
+    /// First day of the cell containing `date`
+    pub fn period_start(&self, date: NaiveDate) -> NaiveDate {
+        self.granularity.period_start(date, self.week_start)
+    }
+
+    /// Number of whole cells from the one containing `from` to the one containing `to`
+    pub fn periods_between(&self, from: NaiveDate, to: NaiveDate) -> i64 {
+        self.granularity.periods_between(from, to, self.week_start)
+    }
+}
+
+/// Survival probability below which a life grid stops extending
+const MIN_SURVIVAL: f64 = 0.01;
+
+/// Average number of weeks in a year
+const WEEKS_PER_YEAR: f64 = 365.25 / 7.0;
+
+/// Working days in a week, for converting public holidays to weeks
+const WORKING_DAYS_PER_WEEK: f64 = 5.0;
+
+/// Length of a pregnancy in weeks, counted from the last menstrual period
+const PREGNANCY_WEEKS: usize = 40;
+
+/// Days from the last menstrual period to the due date
+const PREGNANCY_DAYS: i64 = 280;
+
+/// Days from conception to the due date
+const CONCEPTION_TO_DUE_DAYS: i64 = 266;
+
+/// Estimate a due date from the date of conception
+pub fn due_date_from_conception(conceived: NaiveDate) -> NaiveDate {
+    conceived + chrono::Duration::days(CONCEPTION_TO_DUE_DAYS)
+}
+
 /// Status of a single week
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum WeekStatus {
@@ -59,23 +579,65 @@ pub enum WeekStatus {
     Future,
 }
 
-/// A single week in the grid
+/// What a week is used for, in modes that distinguish kinds of weeks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WeekCategory {
+    /// An ordinary (teaching) week
+    #[default]
+    Regular,
+    /// A holiday or break week
+    Break,
+    /// An exam week
+    Exam,
+    /// A week off work, such as vacation or public holidays
+    Free,
+}
+
+/// A single cell in the grid
+///
+/// A cell covers one week by default, or one day, month or year depending on
+/// the grid's `Granularity`.
 #[derive(Debug, Clone)]
 pub struct Week {
-    /// Start date of this week (Monday)
+    /// First day of this week (per `GridOptions::week_start`)
     pub start_date: NaiveDate,
+    /// Last day covered by this cell (inclusive)
+    pub end_date: NaiveDate,
     /// Status of this week
     pub status: WeekStatus,
     /// Optional label (e.g., year marker)
     pub label: Option<String>,
     /// Year this week belongs to (for life mode year markers)
     pub year: i32,
-    /// Week number within the year (1-52/53)
+    /// Week number within the year (1-52/53, per `GridOptions::week_numbering`)
     pub week_of_year: u32,
+    /// Index into `WeekGrid::legend` if an event falls in this week
+    pub event: Option<usize>,
+    /// Index into `WeekGrid::chapters` if this week is part of a chapter
+    pub chapter: Option<usize>,
+    /// Probability of still being alive in this week, for survival shading
+    pub survival: Option<f64>,
+    /// Indices into `WeekGrid::people` of everyone alive during this week
+    pub people: Vec<usize>,
+    /// Kind of week, such as a break within an academic term
+    pub category: WeekCategory,
+    /// Journal rating (1-5) for this week, averaged when a cell spans several weeks
+    pub rating: Option<u8>,
+    /// Sum of the heatmap values dated within this cell
+    pub value: Option<f64>,
+}
+
+/// A named, colored entry shown in the wallpaper legend
+#[derive(Debug, Clone, PartialEq)]
+pub struct LegendEntry {
+    /// Text shown next to the swatch
+    pub label: String,
+    /// Optional hex color; the renderer picks a palette color when omitted
+    pub color: Option<String>,
 }
 
 /// Grid of weeks for rendering
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct WeekGrid {
     /// All weeks in the grid
     pub weeks: Vec<Week>,
@@ -93,59 +655,100 @@ pub struct WeekGrid {
     pub title: String,
     /// Subtitle with stats
     pub subtitle: String,
+    /// Highlighted events referenced by `Week::event`
+    pub legend: Vec<LegendEntry>,
+    /// Life chapters referenced by `Week::chapter`
+    pub chapters: Vec<LegendEntry>,
+    /// People referenced by `Week::people` in family mode
+    pub people: Vec<LegendEntry>,
+    /// Time unit of each cell
+    pub granularity: Granularity,
+    /// Index of the first week of each row; empty when weeks simply wrap every `columns`
+    pub row_starts: Vec<usize>,
+    /// Columns per group, set apart by a wider gap; 0 disables grouping
+    pub column_group: usize,
+    /// Caption each column group with the label of its first week
+    pub column_captions: bool,
+    /// Rows per block, set apart by a wider gap; 0 disables grouping
+    pub row_group: usize,
+    /// Caption each block of rows with its first and last row labels
+    pub row_captions: bool,
+    /// What `Week::value` measures, when a heatmap is shown
+    pub value_label: Option<String>,
 }
 
 impl WeekGrid {
     /// Calculate the grid based on the mode
     pub fn calculate(mode: &Mode) -> Self {
-        let today = Local::now().date_naive();
+        Self::calculate_with(mode, &GridOptions::default())
+    }
+
+    /// Calculate the grid as it looks on the given date
+    pub fn calculate_at(mode: &Mode, today: NaiveDate) -> Self {
+        Self::calculate_with(
+            mode,
+            &GridOptions {
+                as_of: Some(today),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Calculate the grid based on the mode and calendar options
+    pub fn calculate_with(mode: &Mode, options: &GridOptions) -> Self {
+        let today = options.as_of.unwrap_or_else(|| Local::now().date_naive());
 
         match mode {
-            Mode::NextMonths { months } => Self::calculate_next_months(*months, today),
-            Mode::YearEnd => Self::calculate_year_end(today),
+            Mode::NextMonths { months } => Self::calculate_next_months(*months, today, options),
+            Mode::YearEnd => Self::calculate_year_end(today, options),
             Mode::Life {
                 dob,
                 lifespan_years,
-            } => Self::calculate_life(*dob, *lifespan_years, today),
+                age_rows,
+            } => Self::calculate_life(*dob, *lifespan_years, *age_rows, today, options),
+            Mode::Range { start, end, title } => {
+                Self::calculate_range(*start, *end, title.as_deref(), today, options)
+            }
+            Mode::Countdown { targets } => Self::calculate_countdown(targets, today, options),
+            Mode::FullYear { month_rows } => Self::calculate_full_year(*month_rows, today, options),
+            Mode::Family { people } => Self::calculate_family(people, today, options),
+            Mode::TailEnd {
+                dob,
+                lifespan_years,
+                items,
+            } => Self::calculate_tail_end(*dob, *lifespan_years, items, today, options),
+            Mode::Retirement {
+                dob,
+                retire_on,
+                vacation_weeks,
+                holiday_days,
+            } => Self::calculate_retirement(
+                *dob,
+                *retire_on,
+                *vacation_weeks,
+                *holiday_days,
+                today,
+                options,
+            ),
+            Mode::Term { terms } => Self::calculate_term(terms, today, options),
+            Mode::Fiscal { start_month } => Self::calculate_fiscal(*start_month, today, options),
+            Mode::Sprint {
+                anchor,
+                release,
+                sprint_weeks,
+            } => Self::calculate_sprints(*anchor, *release, *sprint_weeks, today, options),
+            Mode::Pregnancy { due, born } => Self::calculate_pregnancy(*due, *born, today, options),
         }
     }
 
     /// Calculate weeks for the next N months
-    fn calculate_next_months(months: u8, today: NaiveDate) -> Self {
-        let start = week_start(today);
+    fn calculate_next_months(months: u8, today: NaiveDate, options: &GridOptions) -> Self {
         let end_date = add_months(today, months as i32);
-        let end = week_start(end_date);
-
-        let mut weeks = Vec::new();
-        let mut current = start;
-        let mut current_week_index = None;
-
-        while current <= end {
-            let status = if current <= today && today < current + chrono::Duration::days(7) {
-                current_week_index = Some(weeks.len());
-                WeekStatus::Current
-            } else if current < today {
-                WeekStatus::Past
-            } else {
-                WeekStatus::Future
-            };
-
-            weeks.push(Week {
-                start_date: current,
-                status,
-                label: None,
-                year: current.year(),
-                week_of_year: current.iso_week().week(),
-            });
-
-            current += chrono::Duration::days(7);
-        }
+        let (weeks, current_week_index) = build_weeks(today, end_date, today, options);
+        let unit = options.granularity.plural();
 
         let total_weeks = weeks.len();
-        let elapsed_weeks = weeks
-            .iter()
-            .filter(|w| w.status == WeekStatus::Past)
-            .count();
+        let elapsed_weeks = count_past(&weeks);
 
         // Calculate grid dimensions (prefer wider layout)
         let columns = (total_weeks as f64).sqrt().ceil() as usize;
@@ -159,51 +762,30 @@ impl WeekGrid {
             columns,
             rows,
             title: format!("Next {} Months", months),
-            subtitle: format!("{} weeks remaining", total_weeks - elapsed_weeks - 1),
+            subtitle: format!("{} {} remaining", total_weeks - elapsed_weeks - 1, unit),
+            granularity: options.granularity,
+            ..Default::default()
         }
     }
 
     /// Calculate weeks until end of year
-    fn calculate_year_end(today: NaiveDate) -> Self {
-        let start = week_start(today);
+    fn calculate_year_end(today: NaiveDate, options: &GridOptions) -> Self {
         let year_end = NaiveDate::from_ymd_opt(today.year(), 12, 31).unwrap();
-        let end = week_start(year_end);
-
-        let mut weeks = Vec::new();
-        let mut current = start;
-        let mut current_week_index = None;
-
-        while current <= end {
-            let status = if current <= today && today < current + chrono::Duration::days(7) {
-                current_week_index = Some(weeks.len());
-                WeekStatus::Current
-            } else if current < today {
-                WeekStatus::Past
-            } else {
-                WeekStatus::Future
-            };
-
-            weeks.push(Week {
-                start_date: current,
-                status,
-                label: None,
-                year: current.year(),
-                week_of_year: current.iso_week().week(),
-            });
-
-            current += chrono::Duration::days(7);
-        }
+        let (weeks, current_week_index) = build_weeks(today, year_end, today, options);
+        let unit = options.granularity.plural();
 
         let total_weeks = weeks.len();
-        let elapsed_weeks = weeks
-            .iter()
-            .filter(|w| w.status == WeekStatus::Past)
-            .count();
+        let elapsed_weeks = count_past(&weeks);
         let remaining = total_weeks - elapsed_weeks - 1;
 
-        // Single row for year-end mode
-        let columns = total_weeks;
-        let rows = 1;
+        // Single row for year-end mode; days wrap into a near-square block
+        let (columns, rows) = match options.granularity {
+            Granularity::Day => {
+                let columns = (total_weeks as f64).sqrt().ceil() as usize;
+                (columns, total_weeks.div_ceil(columns))
+            }
+            _ => (total_weeks, 1),
+        };
 
         Self {
             weeks,
@@ -212,63 +794,63 @@ impl WeekGrid {
             current_week_index,
             columns,
             rows,
-            title: format!("Weeks Remaining in {}", today.year()),
-            subtitle: format!("{} weeks to go", remaining),
+            title: format!("{} Remaining in {}", capitalize(unit), today.year()),
+            subtitle: format!("{} {} to go", remaining, unit),
+            granularity: options.granularity,
+            ..Default::default()
         }
     }
 
     /// Calculate life in weeks from DOB to expected lifespan
-    fn calculate_life(dob: NaiveDate, lifespan_years: u8, today: NaiveDate) -> Self {
-        // Start from the Monday of the week containing DOB
-        let start = week_start(dob);
-        // End at expected lifespan
+    ///
+    /// With `age_rows`, each row starts at the cell holding a birthday and is
+    /// labeled with the age, so rows hold 52 or 53 weeks and never drift.
+    fn calculate_life(
+        dob: NaiveDate,
+        lifespan_years: u8,
+        age_rows: bool,
+        today: NaiveDate,
+        options: &GridOptions,
+    ) -> Self {
+        // Run from the period containing DOB to the expected lifespan
         let expected_end = add_years(dob, lifespan_years as i32);
-        let end = week_start(expected_end);
-
-        let mut weeks = Vec::new();
-        let mut current = start;
-        let mut current_week_index = None;
-        let mut last_year = dob.year();
-
-        while current <= end {
-            let status = if current <= today && today < current + chrono::Duration::days(7) {
-                current_week_index = Some(weeks.len());
-                WeekStatus::Current
-            } else if current < today {
-                WeekStatus::Past
-            } else {
-                WeekStatus::Future
-            };
+        let (mut weeks, mut current_week_index) = build_weeks(dob, expected_end, today, options);
+        let unit = options.granularity.plural();
 
+        // Coarser cells can't start mid-year, so rows already follow the calendar
+        let age_rows =
+            age_rows && matches!(options.granularity, Granularity::Day | Granularity::Week);
+        let mut row_starts = Vec::new();
+        if age_rows {
+            // The last row ends just before the cell holding the final birthday
+            weeks.truncate(options.periods_between(dob, expected_end) as usize);
+            current_week_index = current_week_index.filter(|&i| i < weeks.len());
+            row_starts = age_row_starts(&mut weeks, dob, options);
+        } else {
             // Add year label at the start of each new year
-            let label = if current.year() != last_year {
-                last_year = current.year();
-                Some(format!("{}", current.year()))
-            } else {
-                None
-            };
-
-            weeks.push(Week {
-                start_date: current,
-                status,
-                label,
-                year: current.year(),
-                week_of_year: current.iso_week().week(),
-            });
-
-            current += chrono::Duration::days(7);
+            let mut last_year = dob.year();
+            for week in &mut weeks {
+                if week.start_date.year() != last_year {
+                    last_year = week.start_date.year();
+                    week.label = Some(format!("{}", last_year));
+                }
+            }
         }
 
         let total_weeks = weeks.len();
-        let elapsed_weeks = weeks
-            .iter()
-            .filter(|w| w.status == WeekStatus::Past)
-            .count();
+        let elapsed_weeks = count_past(&weeks);
         let remaining = total_weeks.saturating_sub(elapsed_weeks + 1);
 
-        // Life mode: 52 columns (weeks per year) x lifespan rows
-        let columns = 52;
-        let rows = total_weeks.div_ceil(columns);
+        // One row per year of life (52 columns for weeks); years wrap by decade
+        let (columns, rows) = if age_rows {
+            (longest_row(&row_starts, total_weeks), row_starts.len())
+        } else {
+            let columns = match options.granularity {
+                Granularity::Year => 10,
+                granularity => granularity.per_year(),
+            };
+            (columns, total_weeks.div_ceil(columns))
+        };
 
         let age_years = (today - dob).num_days() / 365;
         let percentage = (elapsed_weeks as f64 / total_weeks as f64 * 100.0) as u32;
@@ -280,101 +862,2367 @@ impl WeekGrid {
             current_week_index,
             columns,
             rows,
-            title: format!("Life in Weeks (Age {})", age_years),
+            title: format!("Life in {} (Age {})", capitalize(unit), age_years),
             subtitle: format!(
-                "{} of {} weeks lived ({}%) - {} remaining",
-                elapsed_weeks, total_weeks, percentage, remaining
+                "{} of {} {} lived ({}%) - {} remaining",
+                elapsed_weeks, total_weeks, unit, percentage, remaining
             ),
+            granularity: options.granularity,
+            row_starts,
+            ..Default::default()
         }
     }
-}
 
-/// Get the Monday of the week containing the given date
-fn week_start(date: NaiveDate) -> NaiveDate {
-    let weekday = date.weekday();
-    let days_since_monday = weekday.num_days_from_monday();
-    date - chrono::Duration::days(days_since_monday as i64)
-}
+    /// Calculate weeks for an arbitrary date range
+    fn calculate_range(
+        start: NaiveDate,
+        end: NaiveDate,
+        title: Option<&str>,
+        today: NaiveDate,
+        options: &GridOptions,
+    ) -> Self {
+        let (weeks, current_week_index) = build_weeks(start, end, today, options);
+        let unit = options.granularity.plural();
 
-/// Add months to a date
-fn add_months(date: NaiveDate, months: i32) -> NaiveDate {
-    let total_months = date.month() as i32 + months;
-    let years_to_add = (total_months - 1) / 12;
-    let new_month = ((total_months - 1) % 12 + 1) as u32;
-    let new_year = date.year() + years_to_add;
+        let total_weeks = weeks.len();
+        let elapsed_weeks = count_past(&weeks);
+        let remaining = total_weeks - elapsed_weeks - usize::from(current_week_index.is_some());
 
-    // Handle month overflow (e.g., Jan 31 + 1 month)
-    let max_day = days_in_month(new_year, new_month);
-    let new_day = date.day().min(max_day);
+        // Same near-square layout as next-months mode
+        let columns = (total_weeks as f64).sqrt().ceil() as usize;
+        let rows = total_weeks.div_ceil(columns);
 
-    NaiveDate::from_ymd_opt(new_year, new_month, new_day).unwrap()
-}
+        let percentage = (elapsed_weeks as f64 / total_weeks as f64 * 100.0) as u32;
+        let subtitle = if today < start {
+            format!(
+                "{} {} - starts in {} {}",
+                total_weeks,
+                unit,
+                options.periods_between(today, start),
+                unit
+            )
+        } else {
+            format!(
+                "{} of {} {} elapsed ({}%) - {} remaining",
+                elapsed_weeks, total_weeks, unit, percentage, remaining
+            )
+        };
 
-/// Add years to a date
-fn add_years(date: NaiveDate, years: i32) -> NaiveDate {
-    let new_year = date.year() + years;
-    // Handle Feb 29 on non-leap years
-    let max_day = days_in_month(new_year, date.month());
-    let new_day = date.day().min(max_day);
+        Self {
+            weeks,
+            total_weeks,
+            elapsed_weeks,
+            current_week_index,
+            columns,
+            rows,
+            title: title
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("{} to {}", start, end)),
+            subtitle,
+            granularity: options.granularity,
+            ..Default::default()
+        }
+    }
 
-    NaiveDate::from_ymd_opt(new_year, date.month(), new_day).unwrap()
-}
+    /// Calculate weeks from now until the last countdown target
+    fn calculate_countdown(
+        targets: &[CountdownTarget],
+        today: NaiveDate,
+        options: &GridOptions,
+    ) -> Self {
+        let mut targets = targets.to_vec();
+        targets.sort_by_key(|t| t.date);
 
-/// Get the number of days in a month
-fn days_in_month(year: i32, month: u32) -> u32 {
-    match month {
-        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
-        4 | 6 | 9 | 11 => 30,
-        2 => {
-            if is_leap_year(year) {
-                29
-            } else {
-                28
+        let last = targets.last().map_or(today, |t| t.date.max(today));
+        let (mut weeks, current_week_index) = build_weeks(today, last, today, options);
+        let unit = options.granularity.plural();
+
+        // Mark each upcoming target and give it its own subtitle line
+        let mut legend = Vec::new();
+        let mut lines = Vec::new();
+        for target in &targets {
+            let periods_left = options.periods_between(today, target.date);
+            if periods_left < 0 {
+                lines.push(format!("{} - passed", target.name));
+                continue;
             }
+
+            let week = &mut weeks[periods_left as usize];
+            if week.event.is_none() {
+                week.event = Some(legend.len());
+            }
+            legend.push(LegendEntry {
+                label: target.name.clone(),
+                color: target.color.clone(),
+            });
+            lines.push(format!("{} - {} {} left", target.name, periods_left, unit));
         }
-        _ => 30,
-    }
-}
 
-/// Check if a year is a leap year
-fn is_leap_year(year: i32) -> bool {
-    (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0)
-}
+        let total_weeks = weeks.len();
+        let elapsed_weeks = count_past(&weeks);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use chrono::Weekday;
+        let columns = (total_weeks as f64).sqrt().ceil() as usize;
+        let rows = total_weeks.div_ceil(columns);
 
-    #[test]
-    fn test_week_start() {
-        // Test with a Wednesday
-        let wed = NaiveDate::from_ymd_opt(2024, 1, 10).unwrap();
-        let monday = week_start(wed);
-        assert_eq!(monday.weekday(), Weekday::Mon);
-        assert_eq!(monday, NaiveDate::from_ymd_opt(2024, 1, 8).unwrap());
+        let title = match targets.as_slice() {
+            [target] => format!("Countdown to {}", target.name),
+            _ => "Countdown".to_string(),
+        };
+
+        Self {
+            weeks,
+            total_weeks,
+            elapsed_weeks,
+            current_week_index,
+            columns,
+            rows,
+            title,
+            subtitle: lines.join("\n"),
+            legend,
+            granularity: options.granularity,
+            ..Default::default()
+        }
     }
 
-    #[test]
-    fn test_life_mode() {
-        let dob = NaiveDate::from_ymd_opt(1990, 1, 1).unwrap();
-        let mode = Mode::Life {
-            dob,
-            lifespan_years: 80,
+    /// Calculate the whole current year, from January 1 to December 31
+    fn calculate_full_year(month_rows: bool, today: NaiveDate, options: &GridOptions) -> Self {
+        let year = today.year();
+        let first_day = NaiveDate::from_ymd_opt(year, 1, 1).unwrap();
+        let last_day = NaiveDate::from_ymd_opt(year, 12, 31).unwrap();
+        let (mut weeks, current_week_index) = build_weeks(first_day, last_day, today, options);
+        let unit = options.granularity;
+
+        let total_weeks = weeks.len();
+        let elapsed_weeks = count_past(&weeks);
+        let percentage = (elapsed_weeks as f64 / total_weeks as f64 * 100.0) as u32;
+
+        // Weeks are counted by the configured numbering scheme, whose first
+        // and last weeks may belong to the neighbouring years
+        let (current_number, number_of) = match (unit, current_week_index) {
+            (Granularity::Week, Some(index)) => {
+                let number = weeks[index].week_of_year;
+                let numbered_year = match (index, number) {
+                    (0, n) if n > 1 => year - 1,
+                    (i, 1) if i > 0 => year + 1,
+                    _ => year,
+                };
+                let weeks_in_year = options
+                    .week_numbering
+                    .weeks_in_year(numbered_year, options.week_start);
+                (number as usize, weeks_in_year as usize)
+            }
+            _ => (
+                current_week_index.map_or(elapsed_weeks, |i| i + 1),
+                total_weeks,
+            ),
         };
-        let grid = WeekGrid::calculate(&mode);
 
-        // 80 years * 52 weeks ≈ 4160 weeks
-        assert!(grid.total_weeks >= 4160 && grid.total_weeks <= 4200);
-        assert_eq!(grid.columns, 52);
-    }
+        // Month rows only make sense when a month spans several cells
+        let month_rows = month_rows && matches!(unit, Granularity::Day | Granularity::Week);
+        let mut row_starts = Vec::new();
+        if month_rows {
+            let mut last_month = 0;
+            for (i, week) in weeks.iter_mut().enumerate() {
+                // A week belongs to the month holding most of its days,
+                // clamped so the edge weeks stay in January and December
+                let midpoint = match unit {
+                    Granularity::Week => week.start_date + chrono::Duration::days(3),
+                    _ => week.start_date,
+                };
+                let month = midpoint.clamp(first_day, last_day).month();
+                if month != last_month {
+                    last_month = month;
+                    row_starts.push(i);
+                    week.label = Some(midpoint.clamp(first_day, last_day).format("%b").to_string());
+                }
+            }
+        }
 
-    #[test]
-    fn test_year_end_mode() {
-        let grid = WeekGrid::calculate(&Mode::YearEnd);
+        let (columns, rows) = if month_rows {
+            (longest_row(&row_starts, total_weeks), row_starts.len())
+        } else if unit == Granularity::Day {
+            let columns = (total_weeks as f64).sqrt().ceil() as usize;
+            (columns, total_weeks.div_ceil(columns))
+        } else {
+            (total_weeks, 1)
+        };
 
-        // Should have weeks until end of year
-        assert!(grid.total_weeks > 0);
-        assert!(grid.total_weeks <= 53);
+        Self {
+            weeks,
+            total_weeks,
+            elapsed_weeks,
+            current_week_index,
+            columns,
+            rows,
+            title: format!("{} in {}", year, capitalize(unit.plural())),
+            subtitle: format!(
+                "{} {} of {} ({}%)",
+                capitalize(unit.singular()),
+                current_number,
+                number_of,
+                percentage
+            ),
+            granularity: unit,
+            row_starts,
+            ..Default::default()
+        }
+    }
+
+    /// Calculate several lives on one grid with one row per calendar year
+    ///
+    /// Rows run from January 1 of the earliest birth year to December 31 of
+    /// the latest expected end, so shared weeks line up across people.
+    fn calculate_family(people: &[Person], today: NaiveDate, options: &GridOptions) -> Self {
+        let unit = options.granularity;
+        let spans: Vec<(NaiveDate, NaiveDate)> = people
+            .iter()
+            .map(|p| {
+                let end = add_years(p.dob, p.lifespan_years.unwrap_or(80) as i32);
+                (p.dob, end.pred_opt().unwrap())
+            })
+            .collect();
+
+        let first_year = spans
+            .iter()
+            .map(|s| s.0.year())
+            .min()
+            .unwrap_or(today.year());
+        let last_year = spans
+            .iter()
+            .map(|s| s.1.year())
+            .max()
+            .unwrap_or(today.year());
+        let first_day = NaiveDate::from_ymd_opt(first_year, 1, 1).unwrap();
+        let last_day = NaiveDate::from_ymd_opt(last_year, 12, 31).unwrap();
+        let (mut weeks, current_week_index) = build_weeks(first_day, last_day, today, options);
+
+        let mut row_starts = Vec::new();
+        let mut last_row_year = None;
+        for (i, week) in weeks.iter_mut().enumerate() {
+            week.people = spans
+                .iter()
+                .enumerate()
+                .filter(|(_, &(start, end))| week.start_date <= end && week.end_date >= start)
+                .map(|(person, _)| person)
+                .collect();
+
+            // A week belongs to the year holding most of its days
+            if unit != Granularity::Year {
+                let midpoint = match unit {
+                    Granularity::Week => week.start_date + chrono::Duration::days(3),
+                    _ => week.start_date,
+                };
+                let year = midpoint.clamp(first_day, last_day).year();
+                if last_row_year != Some(year) {
+                    last_row_year = Some(year);
+                    row_starts.push(i);
+                    week.label = Some(format!("{}", year));
+                }
+            }
+        }
+
+        let total_weeks = weeks.len();
+        let elapsed_weeks = count_past(&weeks);
+        let (columns, rows) = if row_starts.is_empty() {
+            (10, total_weeks.div_ceil(10))
+        } else {
+            (longest_row(&row_starts, total_weeks), row_starts.len())
+        };
+
+        let lines: Vec<String> = people
+            .iter()
+            .zip(&spans)
+            .map(|(person, &(dob, end))| {
+                if today < dob {
+                    format!(
+                        "{} - arrives in {} {}",
+                        person.name,
+                        options.periods_between(today, dob),
+                        unit.plural()
+                    )
+                } else if today > end {
+                    format!("{} - past expected lifespan", person.name)
+                } else {
+                    format!(
+                        "{} (age {}) - {} {} left",
+                        person.name,
+                        (today - dob).num_days() / 365,
+                        options.periods_between(today, end),
+                        unit.plural()
+                    )
+                }
+            })
+            .collect();
+
+        Self {
+            weeks,
+            total_weeks,
+            elapsed_weeks,
+            current_week_index,
+            columns,
+            rows,
+            title: format!("Our Lives in {}", capitalize(unit.plural())),
+            subtitle: lines.join("\n"),
+            people: people
+                .iter()
+                .map(|p| LegendEntry {
+                    label: p.name.clone(),
+                    color: p.color.clone(),
+                })
+                .collect(),
+            granularity: unit,
+            row_starts,
+            ..Default::default()
+        }
+    }
+
+    /// Calculate the remaining occurrences of each recurring item
+    ///
+    /// Cells are occurrences rather than calendar periods: each item gets its
+    /// own block of rows, with past occurrences filled in the item's color and
+    /// future ones left empty.
+    fn calculate_tail_end(
+        dob: NaiveDate,
+        lifespan_years: u8,
+        items: &[RecurringItem],
+        today: NaiveDate,
+        options: &GridOptions,
+    ) -> Self {
+        let expected_end = add_years(dob, lifespan_years as i32);
+        let years = |from: NaiveDate, to: NaiveDate| (to - from).num_days().max(0) as f64 / 365.25;
+
+        let counts: Vec<(usize, usize)> = items
+            .iter()
+            .map(|item| {
+                let start = item.since.unwrap_or(dob);
+                let end = item.until.unwrap_or(expected_end);
+                let total = (years(start, end) * item.per_year).round() as usize;
+                let done = (years(start, today.min(end)) * item.per_year).floor() as usize;
+                (total, done.min(total))
+            })
+            .collect();
+
+        let total_weeks: usize = counts.iter().map(|c| c.0).sum();
+        let columns = ((total_weeks as f64).sqrt().ceil() as usize).max(10);
+
+        let mut weeks = Vec::new();
+        let mut row_starts = Vec::new();
+        let mut lines = Vec::new();
+        for (chapter, (item, &(total, done))) in items.iter().zip(&counts).enumerate() {
+            let start = item.since.unwrap_or(dob);
+            let end = item.until.unwrap_or(expected_end);
+            let cells = build_count_cells(start, end, total, done, options);
+            for (i, mut cell) in cells.into_iter().enumerate() {
+                if i % columns == 0 {
+                    row_starts.push(weeks.len());
+                }
+                if i == 0 {
+                    cell.label = Some(item.name.clone());
+                }
+                if cell.status == WeekStatus::Past {
+                    cell.chapter = Some(chapter);
+                }
+                weeks.push(cell);
+            }
+            lines.push(format!(
+                "{} - {} left of {} ({}/year)",
+                item.name,
+                total - done,
+                total,
+                item.per_year
+            ));
+        }
+
+        let elapsed_weeks = count_past(&weeks);
+
+        Self {
+            weeks,
+            total_weeks,
+            elapsed_weeks,
+            current_week_index: None,
+            columns,
+            rows: row_starts.len(),
+            title: "The Tail End".to_string(),
+            subtitle: lines.join("\n"),
+            chapters: items
+                .iter()
+                .map(|item| LegendEntry {
+                    label: item.name.clone(),
+                    color: item.color.clone(),
+                })
+                .collect(),
+            granularity: options.granularity,
+            row_starts,
+            ..Default::default()
+        }
+    }
+
+    /// Calculate the weeks from today until retirement
+    ///
+    /// Vacation weeks and public holidays are spread evenly over each year
+    /// and marked as free weeks (week granularity only), and the subtitle
+    /// counts the working weeks that remain after taking them out.
+    fn calculate_retirement(
+        dob: Option<NaiveDate>,
+        retire_on: NaiveDate,
+        vacation_weeks: u8,
+        holiday_days: u8,
+        today: NaiveDate,
+        options: &GridOptions,
+    ) -> Self {
+        let (mut weeks, current_week_index) =
+            build_weeks(today, retire_on.max(today), today, options);
+        let unit = options.granularity;
+
+        let free_per_year = vacation_weeks as f64 + holiday_days as f64 / WORKING_DAYS_PER_WEEK;
+        let free_ratio = (free_per_year / WEEKS_PER_YEAR).min(1.0);
+        if unit == Granularity::Week {
+            // Start halfway so free weeks sit in the middle of each stretch of work
+            let mut owed = 0.5;
+            for week in weeks.iter_mut().filter(|w| w.status == WeekStatus::Future) {
+                owed += free_ratio;
+                if owed >= 1.0 {
+                    owed -= 1.0;
+                    week.category = WeekCategory::Free;
+                }
+            }
+        }
+
+        let total_weeks = weeks.len();
+        let elapsed_weeks = count_past(&weeks);
+
+        // Count marked cells when cells are weeks, so the subtitle matches the grid
+        let (weeks_left, working_left) = if unit == Granularity::Week {
+            let future = weeks.iter().filter(|w| w.status == WeekStatus::Future);
+            let free = future
+                .clone()
+                .filter(|w| w.category == WeekCategory::Free)
+                .count();
+            let left = future.count();
+            (left as f64, left - free)
+        } else {
+            let left = (retire_on - today).num_days().max(0) as f64 / 7.0;
+            (left, (left * (1.0 - free_ratio)).round() as usize)
+        };
+        let mut lines = vec![if today >= retire_on {
+            format!("Retired on {}", retire_on)
+        } else if free_per_year > 0.0 {
+            format!(
+                "{} working weeks remaining ({} weeks, less {:.1} off per year)",
+                working_left,
+                weeks_left.round(),
+                free_per_year
+            )
+        } else {
+            format!("{} working weeks remaining", working_left)
+        }];
+        if let Some(dob) = dob {
+            lines.push(format!(
+                "Retiring {} at age {}",
+                retire_on,
+                (retire_on - dob).num_days() / 365
+            ));
+        }
+
+        let columns = match unit {
+            Granularity::Year => 10,
+            granularity => granularity.per_year(),
+        };
+        let rows = total_weeks.div_ceil(columns);
+
+        Self {
+            weeks,
+            total_weeks,
+            elapsed_weeks,
+            current_week_index,
+            columns,
+            rows,
+            title: format!("{} to Retirement", capitalize(unit.plural())),
+            subtitle: lines.join("\n"),
+            granularity: unit,
+            ..Default::default()
+        }
+    }
+
+    /// Calculate the current term, or the next one if between terms
+    ///
+    /// A cell counts as a break or exam week when its midpoint falls within
+    /// the break, so a break that only clips a day or two of a week doesn't
+    /// take the whole week out of teaching.
+    fn calculate_term(terms: &[Term], today: NaiveDate, options: &GridOptions) -> Self {
+        let mut terms = terms.to_vec();
+        terms.sort_by_key(|t| t.start);
+        let Some(term) = terms
+            .iter()
+            .find(|t| t.end >= today)
+            .or(terms.last())
+            .cloned()
+        else {
+            // A deserialized mode can bypass `from_str_with_params`; draw nothing
+            return Self {
+                title: "Term".to_string(),
+                subtitle: "No terms configured".to_string(),
+                granularity: options.granularity,
+                ..Default::default()
+            };
+        };
+
+        let (mut weeks, current_week_index) = build_weeks(term.start, term.end, today, options);
+        let unit = options.granularity;
+
+        for week in &mut weeks {
+            let midpoint = match unit {
+                Granularity::Week => week.start_date + chrono::Duration::days(3),
+                _ => week.start_date,
+            };
+            if let Some(period) = term
+                .breaks
+                .iter()
+                .find(|b| b.start <= midpoint && midpoint <= b.end)
+            {
+                week.category = if period.exam {
+                    WeekCategory::Exam
+                } else {
+                    WeekCategory::Break
+                };
+            }
+        }
+
+        let total_weeks = weeks.len();
+        let elapsed_weeks = count_past(&weeks);
+        let teaching = |week: &&Week| week.category == WeekCategory::Regular;
+        let teaching_total = weeks.iter().filter(teaching).count();
+        let teaching_left = weeks
+            .iter()
+            .filter(teaching)
+            .filter(|w| w.status == WeekStatus::Future)
+            .count();
+
+        let subtitle = if today < term.start {
+            format!(
+                "{} teaching {} - starts in {} {}",
+                teaching_total,
+                unit.plural(),
+                options.periods_between(today, term.start),
+                unit.plural()
+            )
+        } else if today > term.end {
+            format!(
+                "Term complete - {} teaching {}",
+                teaching_total,
+                unit.plural()
+            )
+        } else {
+            format!("{} teaching {} left", teaching_left, unit.plural())
+        };
+
+        let columns = (total_weeks as f64).sqrt().ceil() as usize;
+        let rows = total_weeks.div_ceil(columns);
+
+        Self {
+            weeks,
+            total_weeks,
+            elapsed_weeks,
+            current_week_index,
+            columns,
+            rows,
+            title: term.name,
+            subtitle,
+            granularity: unit,
+            ..Default::default()
+        }
+    }
+
+    /// Calculate the fiscal year containing today, one row per quarter
+    ///
+    /// Fiscal years are named after the calendar year they end in, so with a
+    /// July start, July 2024 to June 2025 is FY2025. The current quarter is
+    /// highlighted as a chapter.
+    fn calculate_fiscal(start_month: u32, today: NaiveDate, options: &GridOptions) -> Self {
+        // A deserialized mode can bypass `from_str_with_params`
+        let start_month = start_month.clamp(1, 12);
+        let start_year = if today.month() >= start_month {
+            today.year()
+        } else {
+            today.year() - 1
+        };
+        let first_day = NaiveDate::from_ymd_opt(start_year, start_month, 1).unwrap();
+        let last_day = add_years(first_day, 1).pred_opt().unwrap();
+        let fiscal_year = last_day.year();
+        let (mut weeks, current_week_index) = build_weeks(first_day, last_day, today, options);
+        let unit = options.granularity;
+
+        // A cell belongs to the quarter holding most of its days, clamped so
+        // the edge cells stay in the first and last quarters
+        let quarter_of = |week: &Week| {
+            let midpoint = match unit {
+                Granularity::Week => week.start_date + chrono::Duration::days(3),
+                _ => week.start_date,
+            };
+            let midpoint = midpoint.clamp(first_day, last_day);
+            let months = (midpoint.year() - first_day.year()) * 12 + midpoint.month() as i32
+                - first_day.month() as i32;
+            months as usize / 3
+        };
+        let quarters: Vec<usize> = weeks.iter().map(quarter_of).collect();
+        let current_quarter = current_week_index.map_or(0, |i| quarters[i]);
+
+        let quarter_rows = unit != Granularity::Year;
+        let mut row_starts = Vec::new();
+        for (i, week) in weeks.iter_mut().enumerate() {
+            if quarter_rows && (i == 0 || quarters[i] != quarters[i - 1]) {
+                row_starts.push(i);
+                week.label = Some(format!("Q{}", quarters[i] + 1));
+            }
+            if quarters[i] == current_quarter {
+                week.chapter = Some(0);
+            }
+        }
+
+        let total_weeks = weeks.len();
+        let elapsed_weeks = count_past(&weeks);
+        let left_in = |quarter: Option<usize>| {
+            weeks
+                .iter()
+                .zip(&quarters)
+                .filter(|(w, &q)| {
+                    w.status == WeekStatus::Future && (quarter.is_none() || quarter == Some(q))
+                })
+                .count()
+        };
+
+        let subtitle = format!(
+            "{} {} left in Q{} / {} left in FY{}",
+            left_in(Some(current_quarter)),
+            unit.plural(),
+            current_quarter + 1,
+            left_in(None),
+            fiscal_year
+        );
+
+        let (columns, rows) = if quarter_rows {
+            (longest_row(&row_starts, total_weeks), row_starts.len())
+        } else {
+            (total_weeks, 1)
+        };
+
+        Self {
+            weeks,
+            total_weeks,
+            elapsed_weeks,
+            current_week_index,
+            columns,
+            rows,
+            title: format!("FY{} in {}", fiscal_year, capitalize(unit.plural())),
+            subtitle,
+            chapters: vec![LegendEntry {
+                label: format!("Q{} FY{}", current_quarter + 1, fiscal_year),
+                color: None,
+            }],
+            granularity: unit,
+            row_starts,
+            ..Default::default()
+        }
+    }
+
+    /// Calculate the sprints from `anchor` through the one containing `release`
+    ///
+    /// Weeks are counted from the anchor rather than aligned to the calendar,
+    /// so every sprint starts on the anchor's weekday. Each sprint is a column
+    /// group labelled with its number, and the current sprint is highlighted
+    /// as a chapter.
+    fn calculate_sprints(
+        anchor: NaiveDate,
+        release: NaiveDate,
+        sprint_weeks: u8,
+        today: NaiveDate,
+        options: &GridOptions,
+    ) -> Self {
+        // A deserialized mode can bypass `from_str_with_params`; draw nothing
+        let invalid = match (sprint_weeks, release < anchor) {
+            (0, _) => Some("Sprints must be at least one week long".to_string()),
+            (_, true) => Some(format!(
+                "Release {} is before sprint anchor {}",
+                release, anchor
+            )),
+            _ => None,
+        };
+        if let Some(subtitle) = invalid {
+            return Self {
+                title: "Sprints to Release".to_string(),
+                subtitle,
+                granularity: options.granularity,
+                ..Default::default()
+            };
+        }
+
+        let sprint_len = sprint_weeks as usize;
+        let release_week = ((release - anchor).num_days() / 7) as usize;
+        let sprint_count = release_week / sprint_len + 1;
+        let (mut weeks, current_week_index) =
+            build_anchored_weeks(anchor, sprint_count * sprint_len, today, options);
+
+        let current_sprint = current_week_index.map(|i| i / sprint_len);
+        for (i, week) in weeks.iter_mut().enumerate() {
+            if i % sprint_len == 0 {
+                week.label = Some(format!("S{}", i / sprint_len + 1));
+            }
+            if Some(i / sprint_len) == current_sprint {
+                week.chapter = Some(0);
+            }
+        }
+        weeks[release_week].event = Some(0);
+
+        let total_weeks = weeks.len();
+        let elapsed_weeks = count_past(&weeks);
+        let subtitle = match current_week_index {
+            Some(index) => format!(
+                "Sprint {} of {}, week {} of {} - {} weeks to release",
+                index / sprint_len + 1,
+                sprint_count,
+                index % sprint_len + 1,
+                sprint_len,
+                release_week.saturating_sub(index)
+            ),
+            None if today < anchor => format!(
+                "{} sprints - starts in {} weeks",
+                sprint_count,
+                ((anchor - today).num_days() + 6) / 7
+            ),
+            None => format!("{} sprints - released {}", sprint_count, release),
+        };
+
+        // Whole sprints per row, keeping the grid roughly square
+        let sprints_per_row = ((total_weeks as f64).sqrt() / sprint_len as f64).ceil() as usize;
+        let columns = sprints_per_row.max(1) * sprint_len;
+        let rows = total_weeks.div_ceil(columns);
+
+        Self {
+            weeks,
+            total_weeks,
+            elapsed_weeks,
+            current_week_index,
+            columns,
+            rows,
+            title: "Sprints to Release".to_string(),
+            subtitle,
+            legend: vec![LegendEntry {
+                label: "Release".to_string(),
+                color: None,
+            }],
+            chapters: current_sprint
+                .map(|sprint| LegendEntry {
+                    label: format!("Sprint {}", sprint + 1),
+                    color: None,
+                })
+                .into_iter()
+                .collect(),
+            granularity: Granularity::Week,
+            column_group: sprint_len,
+            column_captions: true,
+            ..Default::default()
+        }
+    }
+
+    /// Calculate a 40-week pregnancy, switching to the baby's first year after birth
+    ///
+    /// Weeks are counted from the first day of the last menstrual period
+    /// (280 days before the due date), not aligned to the calendar, so
+    /// granularity and week start don't apply.
+    fn calculate_pregnancy(
+        due: NaiveDate,
+        born: Option<NaiveDate>,
+        today: NaiveDate,
+        options: &GridOptions,
+    ) -> Self {
+        let birth = born.unwrap_or(due);
+        if today >= birth {
+            return Self::calculate_first_year(birth, today, options);
+        }
+
+        let lmp = due - chrono::Duration::days(PREGNANCY_DAYS);
+        let (mut weeks, current_week_index) =
+            build_anchored_weeks(lmp, PREGNANCY_WEEKS, today, options);
+
+        // Mark trimesters as chapters: weeks 1-13, 14-27 and 28-40
+        let chapters = ["First trimester", "Second trimester", "Third trimester"]
+            .iter()
+            .map(|name| LegendEntry {
+                label: name.to_string(),
+                color: None,
+            })
+            .collect();
+        for (i, week) in weeks.iter_mut().enumerate() {
+            week.chapter = Some(match i {
+                0..=12 => 0,
+                13..=26 => 1,
+                _ => 2,
+            });
+            if i == 0 || i == 13 || i == 27 {
+                week.label = Some(format!("Week {}", i + 1));
+            }
+        }
+
+        let total_weeks = weeks.len();
+        let elapsed_weeks = count_past(&weeks);
+        let subtitle = match current_week_index {
+            Some(index) => format!(
+                "Week {}, {} to go",
+                index + 1,
+                total_weeks.saturating_sub(index + 1)
+            ),
+            None if today < lmp => {
+                format!("Starts in {} weeks", ((lmp - today).num_days() + 6) / 7)
+            }
+            None => format!("Due {}", due),
+        };
+
+        Self {
+            weeks,
+            total_weeks,
+            elapsed_weeks,
+            current_week_index,
+            columns: 10,
+            rows: 4,
+            title: "Pregnancy".to_string(),
+            subtitle,
+            chapters,
+            granularity: Granularity::Week,
+            ..Default::default()
+        }
+    }
+
+    /// Calculate the first 52 weeks after birth
+    fn calculate_first_year(birth: NaiveDate, today: NaiveDate, options: &GridOptions) -> Self {
+        let (mut weeks, current_week_index) = build_anchored_weeks(birth, 52, today, options);
+        for (i, week) in weeks.iter_mut().enumerate().step_by(13) {
+            week.label = Some(format!("Week {}", i + 1));
+        }
+
+        let total_weeks = weeks.len();
+        let elapsed_weeks = count_past(&weeks);
+        let subtitle = match current_week_index {
+            Some(index) => format!(
+                "Week {}, {} to go",
+                index + 1,
+                total_weeks.saturating_sub(index + 1)
+            ),
+            None => "First year complete".to_string(),
+        };
+
+        Self {
+            weeks,
+            total_weeks,
+            elapsed_weeks,
+            current_week_index,
+            columns: 13,
+            rows: 4,
+            title: "Baby's First Year".to_string(),
+            subtitle,
+            granularity: Granularity::Week,
+            ..Default::default()
+        }
+    }
+
+    /// Column and row of the week at `index`
+    pub fn cell_position(&self, index: usize) -> (usize, usize) {
+        if self.row_starts.is_empty() {
+            return (index % self.columns, index / self.columns);
+        }
+        let row = self
+            .row_starts
+            .partition_point(|&start| start <= index)
+            .saturating_sub(1);
+        (index - self.row_starts[row], row)
+    }
+
+    /// Indices of the weeks in `row`
+    pub fn row_range(&self, row: usize) -> std::ops::Range<usize> {
+        let len = self.weeks.len();
+        if self.row_starts.is_empty() {
+            return (row * self.columns).min(len)..((row + 1) * self.columns).min(len);
+        }
+        let start = self.row_starts.get(row).copied().unwrap_or(len);
+        let end = self.row_starts.get(row + 1).copied().unwrap_or(len);
+        start..end
+    }
+
+    /// Text labeling `row`: the label of its first labeled week, or else the
+    /// year of its first week
+    pub fn row_label(&self, row: usize) -> Option<String> {
+        let weeks = &self.weeks[self.row_range(row)];
+        weeks
+            .iter()
+            .find_map(|w| w.label.clone())
+            .or_else(|| weeks.first().map(|w| w.year.to_string()))
+    }
+
+    /// Layer everything configured on top of the mode's cells
+    ///
+    /// Applies survival shading (life mode, when a curve is given), the
+    /// grouping configured for the mode, then chapters, `journal` ratings,
+    /// heatmap data, events and calendars. Tail-end cells count occurrences
+    /// rather than calendar weeks, so only the grouping applies to them.
+    ///
+    /// Returns the warnings for calendar events that had to be skipped.
+    pub fn apply_overlays(
+        &mut self,
+        mode: &Mode,
+        config: &Config,
+        journal: &Journal,
+        survival: Option<&SurvivalCurve>,
+        options: &GridOptions,
+    ) -> anyhow::Result<Vec<String>> {
+        if let (Mode::Life { dob, .. }, Some(curve)) = (mode, survival) {
+            self.apply_survival(*dob, curve, options);
+        }
+        if let Some(grouping) = config.grouping.get(mode.name()) {
+            self.apply_grouping(grouping);
+        }
+        if matches!(mode, Mode::TailEnd { .. }) {
+            return Ok(Vec::new());
+        }
+        self.apply_chapters(&config.chapters);
+        self.apply_journal(journal);
+        if let Some(data) = config.data_series()? {
+            self.apply_data(&data);
+        }
+        self.apply_events(&config.events);
+        let calendars = config.calendars()?;
+        self.apply_calendars(&calendars, &config.category_colors);
+        Ok(calendars
+            .iter()
+            .flat_map(|calendar| {
+                calendar
+                    .warnings
+                    .iter()
+                    .map(move |warning| format!("{}: {}", calendar.name, warning))
+            })
+            .collect())
+    }
+
+    /// Set rows and columns apart into blocks, as configured for the mode
+    ///
+    /// Zero sizes keep the mode's own grouping. Column groups added this way
+    /// are not captioned, since their first weeks rarely carry a label.
+    pub fn apply_grouping(&mut self, grouping: &Grouping) {
+        if grouping.rows > 0 {
+            self.row_group = grouping.rows;
+        }
+        if grouping.columns > 0 && grouping.columns != self.column_group {
+            self.column_group = grouping.columns;
+            self.column_captions = false;
+        }
+        self.row_captions = grouping.captions && self.row_group > 0;
+    }
+
+    /// Mark the weeks containing the given life events and list them in the legend
+    ///
+    /// Events outside the grid's date range are ignored. If several events
+    /// share a week, the first one keeps the cell.
+    pub fn apply_events(&mut self, events: &[LifeEvent]) {
+        for event in events {
+            let Some(index) = self.week_index_of(event.date) else {
+                continue;
+            };
+
+            let legend_index = self.legend.len();
+            self.legend.push(LegendEntry {
+                label: event.name.clone(),
+                color: event.color.clone(),
+            });
+
+            let week = &mut self.weeks[index];
+            if week.event.is_none() {
+                week.event = Some(legend_index);
+            }
+        }
+    }
+
+    /// Mark weeks touched by calendar events, one legend entry per category
+    ///
+    /// An event's category is its first `CATEGORIES` value, or else the
+    /// calendar's name. `colors` maps categories to hex colors; others get a
+    /// palette color. Categories get their own legend entries even when a
+    /// life event shares the name. Weeks already marked by an event keep it.
+    pub fn apply_calendars(&mut self, calendars: &[Calendar], colors: &BTreeMap<String, String>) {
+        let (Some(first), Some(last)) = (self.weeks.first(), self.weeks.last()) else {
+            return;
+        };
+        let (from, to) = (first.start_date, last.end_date);
+        let mut categories: BTreeMap<&str, usize> = BTreeMap::new();
+
+        for calendar in calendars {
+            for event in &calendar.events {
+                let category = event.categories.first().unwrap_or(&calendar.name);
+
+                for (start, end) in event.occurrences(from, to) {
+                    let Some(first_week) = self.week_index_of(start.max(from)) else {
+                        continue;
+                    };
+                    for week in self.weeks[first_week..]
+                        .iter_mut()
+                        .take_while(|w| w.start_date <= end)
+                    {
+                        if week.event.is_some() || week.end_date < start {
+                            continue;
+                        }
+                        let index = *categories.entry(category).or_insert_with(|| {
+                            self.legend.push(LegendEntry {
+                                label: category.clone(),
+                                color: colors
+                                    .iter()
+                                    .find(|(name, _)| name.eq_ignore_ascii_case(category))
+                                    .map(|(_, color)| color.clone()),
+                            });
+                            self.legend.len() - 1
+                        });
+                        week.event = Some(index);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Assign each week to the first chapter overlapping it
+    ///
+    /// Chapters that don't overlap the grid are left out of the legend.
+    pub fn apply_chapters(&mut self, chapters: &[Chapter]) {
+        for chapter in chapters {
+            let chapter_index = self.chapters.len();
+            let mut used = false;
+
+            for week in &mut self.weeks {
+                if week.chapter.is_none()
+                    && week.start_date <= chapter.end
+                    && week.end_date >= chapter.start
+                {
+                    week.chapter = Some(chapter_index);
+                    used = true;
+                }
+            }
+
+            if used {
+                self.chapters.push(LegendEntry {
+                    label: chapter.name.clone(),
+                    color: chapter.color.clone(),
+                });
+            }
+        }
+    }
+
+    /// Attach journal ratings to the past and current weeks they were written for
+    pub fn apply_journal(&mut self, journal: &Journal) {
+        for week in &mut self.weeks {
+            if week.status != WeekStatus::Future {
+                week.rating = journal.rating_between(week.start_date, week.end_date);
+            }
+        }
+    }
+
+    /// Sum heatmap values into the past and current weeks they were recorded in
+    ///
+    /// Weeks after the first recorded value that have none count as zero,
+    /// like a day without commits.
+    pub fn apply_data(&mut self, data: &DataSeries) {
+        let Some(&(first, _)) = data.points.first() else {
+            return;
+        };
+        for week in &mut self.weeks {
+            if week.status != WeekStatus::Future && week.end_date >= first {
+                week.value = Some(
+                    data.total_between(week.start_date, week.end_date)
+                        .unwrap_or(0.0),
+                );
+            }
+        }
+        self.value_label = Some(data.label.clone());
+    }
+
+    /// Shade future weeks of a life grid by the probability of being alive then
+    ///
+    /// Probabilities are conditional on being alive on the grid's reference
+    /// date. The grid is extended past the expected lifespan until the
+    /// probability drops below 1%; `total_weeks` still counts only the weeks
+    /// up to the expected lifespan.
+    pub fn apply_survival(&mut self, dob: NaiveDate, curve: &SurvivalCurve, options: &GridOptions) {
+        let today = options.as_of.unwrap_or_else(|| Local::now().date_naive());
+        let age_at = |date: NaiveDate| (date - dob).num_days() as f64 / 365.25;
+        let age_today = age_at(today);
+        let probability = |date: NaiveDate| curve.survival_probability(age_today, age_at(date));
+
+        // Find where the tail becomes negligible
+        let Some((last_start, last_end)) = self.weeks.last().map(|w| (w.start_date, w.end_date))
+        else {
+            return;
+        };
+        let mut tail_age = age_at(last_end);
+        while tail_age < curve.max_age()
+            && curve.survival_probability(age_today, tail_age) >= MIN_SURVIVAL
+        {
+            tail_age += 0.1;
+        }
+        let tail_end = dob + chrono::Duration::days((tail_age * 365.25) as i64);
+
+        if tail_end > last_end {
+            let age_rows = !self.row_starts.is_empty();
+            let mut last_year = last_start.year();
+            let offset = self.weeks.len();
+            let (mut tail, current) =
+                build_weeks(last_end.succ_opt().unwrap(), tail_end, today, options);
+            for week in tail.iter_mut().filter(|_| !age_rows) {
+                if week.start_date.year() != last_year {
+                    last_year = week.start_date.year();
+                    week.label = Some(format!("{}", last_year));
+                }
+            }
+            if let Some(index) = current {
+                self.current_week_index = Some(offset + index);
+            }
+            self.elapsed_weeks += count_past(&tail);
+            self.weeks.extend(tail);
+            if age_rows {
+                self.row_starts = age_row_starts(&mut self.weeks, dob, options);
+                self.columns = longest_row(&self.row_starts, self.weeks.len());
+                self.rows = self.row_starts.len();
+            } else {
+                self.rows = self.weeks.len().div_ceil(self.columns);
+            }
+        }
+
+        for week in &mut self.weeks {
+            if week.status == WeekStatus::Future {
+                week.survival = Some(probability(week.start_date));
+            }
+        }
+    }
+
+    /// Find the index of the week containing the given date
+    ///
+    /// Cells must be in date order, which holds for every mode but tail-end.
+    pub fn week_index_of(&self, date: NaiveDate) -> Option<usize> {
+        let index = self
+            .weeks
+            .partition_point(|w| w.start_date <= date)
+            .checked_sub(1)?;
+        (date <= self.weeks[index].end_date).then_some(index)
+    }
+}
+
+/// Build consecutive cells from the period containing `start` through the
+/// period containing `end`, returning them with the index of the current cell
+fn build_weeks(
+    start: NaiveDate,
+    end: NaiveDate,
+    today: NaiveDate,
+    options: &GridOptions,
+) -> (Vec<Week>, Option<usize>) {
+    build_cells(
+        options.period_start(start),
+        options.period_start(end),
+        today,
+        options,
+        |date| options.granularity.next_period(date),
+    )
+}
+
+/// Build `count` consecutive 7-day cells starting exactly on `first`
+///
+/// Used when weeks are counted from an event (like a due date) rather than
+/// aligned to the calendar, so granularity and week start are ignored.
+fn build_anchored_weeks(
+    first: NaiveDate,
+    count: usize,
+    today: NaiveDate,
+    options: &GridOptions,
+) -> (Vec<Week>, Option<usize>) {
+    let last = first + chrono::Duration::weeks(count as i64 - 1);
+    build_cells(first, last, today, options, |date| {
+        date + chrono::Duration::days(7)
+    })
+}
+
+/// Build cells starting at `first`, each running until `next_period` of its
+/// start, up to and including the cell starting at `last`
+fn build_cells(
+    first: NaiveDate,
+    last: NaiveDate,
+    today: NaiveDate,
+    options: &GridOptions,
+    next_period: impl Fn(NaiveDate) -> NaiveDate,
+) -> (Vec<Week>, Option<usize>) {
+    let mut weeks = Vec::new();
+    let mut current = first;
+    let mut current_week_index = None;
+
+    while current <= last {
+        let next = next_period(current);
+        let status = if current <= today && today < next {
+            current_week_index = Some(weeks.len());
+            WeekStatus::Current
+        } else if current < today {
+            WeekStatus::Past
+        } else {
+            WeekStatus::Future
+        };
+
+        weeks.push(Week {
+            start_date: current,
+            end_date: next.pred_opt().unwrap(),
+            status,
+            label: None,
+            year: current.year(),
+            week_of_year: options
+                .week_numbering
+                .week_number(current, options.week_start),
+            event: None,
+            chapter: None,
+            survival: None,
+            people: Vec::new(),
+            category: WeekCategory::Regular,
+            rating: None,
+            value: None,
+        });
+
+        current = next;
+    }
+
+    (weeks, current_week_index)
+}
+
+/// Build `count` cells for occurrences of something between `first` and `last`
+///
+/// The first `done` cells are past and the rest future. Each cell is given an
+/// even share of the span as its dates, so events still land near the right
+/// occurrence, but status comes from the count rather than the dates.
+fn build_count_cells(
+    first: NaiveDate,
+    last: NaiveDate,
+    count: usize,
+    done: usize,
+    options: &GridOptions,
+) -> Vec<Week> {
+    let span = (last - first).num_days().max(0) as f64;
+    let date_at =
+        |i: usize| first + chrono::Duration::days((span * i as f64 / count as f64) as i64);
+
+    (0..count)
+        .map(|i| {
+            let start_date = date_at(i);
+            Week {
+                start_date,
+                end_date: date_at(i + 1).pred_opt().unwrap().max(start_date),
+                status: if i < done {
+                    WeekStatus::Past
+                } else {
+                    WeekStatus::Future
+                },
+                label: None,
+                year: start_date.year(),
+                week_of_year: options
+                    .week_numbering
+                    .week_number(start_date, options.week_start),
+                event: None,
+                chapter: None,
+                survival: None,
+                people: Vec::new(),
+                category: WeekCategory::Regular,
+                rating: None,
+                value: None,
+            }
+        })
+        .collect()
+}
+
+/// Start a row at the cell holding each birthday, labeled with the age
+///
+/// A week holding a birthday opens the new age's row even if the birthday
+/// falls later in that week.
+fn age_row_starts(weeks: &mut [Week], dob: NaiveDate, options: &GridOptions) -> Vec<usize> {
+    let mut row_starts = Vec::new();
+    for age in 0.. {
+        let index = options.periods_between(dob, add_years(dob, age)) as usize;
+        let Some(week) = weeks.get_mut(index) else {
+            break;
+        };
+        week.label = Some(age.to_string());
+        row_starts.push(index);
+    }
+    row_starts
+}
+
+/// Length of the longest row when rows begin at `row_starts`
+fn longest_row(row_starts: &[usize], total: usize) -> usize {
+    row_starts
+        .iter()
+        .zip(row_starts.iter().skip(1).chain([&total]))
+        .map(|(start, end)| end - start)
+        .max()
+        .unwrap_or(1)
+}
+
+/// Uppercase the first letter of a unit name ("weeks" -> "Weeks")
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Count the weeks marked as past
+fn count_past(weeks: &[Week]) -> usize {
+    weeks
+        .iter()
+        .filter(|w| w.status == WeekStatus::Past)
+        .count()
+}
+
+/// Get the first day of the week containing the given date
+fn week_start(date: NaiveDate, first_day: WeekStart) -> NaiveDate {
+    date - chrono::Duration::days(first_day.days_into_week(date) as i64)
+}
+
+/// Add months to a date
+fn add_months(date: NaiveDate, months: i32) -> NaiveDate {
+    let total_months = date.month() as i32 + months;
+    let years_to_add = (total_months - 1) / 12;
+    let new_month = ((total_months - 1) % 12 + 1) as u32;
+    let new_year = date.year() + years_to_add;
+
+    // Handle month overflow (e.g., Jan 31 + 1 month)
+    let max_day = days_in_month(new_year, new_month);
+    let new_day = date.day().min(max_day);
+
+    NaiveDate::from_ymd_opt(new_year, new_month, new_day).unwrap()
+}
+
+/// Add years to a date
+fn add_years(date: NaiveDate, years: i32) -> NaiveDate {
+    let new_year = date.year() + years;
+    // Handle Feb 29 on non-leap years
+    let max_day = days_in_month(new_year, date.month());
+    let new_day = date.day().min(max_day);
+
+    NaiveDate::from_ymd_opt(new_year, date.month(), new_day).unwrap()
+}
+
+/// Get the number of days in a month
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 => {
+            if is_leap_year(year) {
+                29
+            } else {
+                28
+            }
+        }
+        _ => 30,
+    }
+}
+
+/// Check if a year is a leap year
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TermBreak;
+
+    #[test]
+    fn test_week_start() {
+        // Test with a Wednesday
+        let wed = NaiveDate::from_ymd_opt(2024, 1, 10).unwrap();
+        let monday = week_start(wed, WeekStart::Monday);
+        assert_eq!(monday.weekday(), Weekday::Mon);
+        assert_eq!(monday, NaiveDate::from_ymd_opt(2024, 1, 8).unwrap());
+
+        let sunday = week_start(wed, WeekStart::Sunday);
+        assert_eq!(sunday, NaiveDate::from_ymd_opt(2024, 1, 7).unwrap());
+        let saturday = week_start(wed, WeekStart::Saturday);
+        assert_eq!(saturday, NaiveDate::from_ymd_opt(2024, 1, 6).unwrap());
+        assert_eq!(week_start(saturday, WeekStart::Saturday), saturday);
+    }
+
+    #[test]
+    fn test_week_numbering() {
+        // 2021 starts on a Friday, so ISO week 1 begins January 4
+        let jan4 = NaiveDate::from_ymd_opt(2021, 1, 4).unwrap();
+        let dec27 = NaiveDate::from_ymd_opt(2020, 12, 27).unwrap();
+        let jan3 = NaiveDate::from_ymd_opt(2021, 1, 3).unwrap();
+
+        assert_eq!(WeekNumbering::Iso.week_number(jan4, WeekStart::Monday), 1);
+        assert_eq!(WeekNumbering::Iso.week_number(jan3, WeekStart::Sunday), 1);
+        assert_eq!(WeekNumbering::Us.week_number(dec27, WeekStart::Sunday), 1);
+        assert_eq!(WeekNumbering::Us.week_number(jan3, WeekStart::Sunday), 2);
+        assert_eq!(
+            WeekNumbering::Simple.week_number(jan4, WeekStart::Monday),
+            1
+        );
+        assert_eq!(
+            WeekNumbering::Simple.week_number(jan4 + chrono::Duration::days(4), WeekStart::Monday),
+            2
+        );
+
+        let grid = WeekGrid::calculate_with(
+            &Mode::Range {
+                start: NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(),
+                end: NaiveDate::from_ymd_opt(2021, 1, 31).unwrap(),
+                title: None,
+            },
+            &GridOptions {
+                week_start: WeekStart::Sunday,
+                week_numbering: WeekNumbering::Us,
+                ..Default::default()
+            },
+        );
+        assert_eq!(grid.weeks[0].start_date, dec27);
+        assert!(grid
+            .weeks
+            .iter()
+            .all(|w| w.start_date.weekday() == Weekday::Sun));
+        let numbers: Vec<u32> = grid.weeks.iter().map(|w| w.week_of_year).collect();
+        assert_eq!(numbers, vec![1, 2, 3, 4, 5, 6]);
+        assert!(WeekStart::parse("friday").is_err());
+        assert!(WeekNumbering::parse("us").is_ok());
+    }
+
+    /// Fixed reference date so tests don't depend on the current date
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, 13).unwrap()
+    }
+
+    #[test]
+    fn test_life_mode() {
+        let dob = NaiveDate::from_ymd_opt(1990, 1, 1).unwrap();
+        let mode = Mode::Life {
+            dob,
+            lifespan_years: 80,
+            age_rows: false,
+        };
+        let grid = WeekGrid::calculate_at(&mode, today());
+
+        // 80 years * 52 weeks ≈ 4160 weeks
+        assert!(grid.total_weeks >= 4160 && grid.total_weeks <= 4200);
+        assert_eq!(grid.columns, 52);
+        assert_eq!(grid.title, "Life in Weeks (Age 34)");
+        // Jan 1 1990 was a Monday, so every week up to the current one has passed
+        assert_eq!(
+            grid.elapsed_weeks,
+            ((today() - dob).num_days() / 7) as usize
+        );
+        assert_eq!(grid.current_week_index, Some(grid.elapsed_weeks));
+        // Rows are labeled with the year that begins in them
+        assert_eq!(grid.row_label(0).as_deref(), Some("1990"));
+        assert_eq!(grid.row_label(1).as_deref(), Some("1991"));
+    }
+
+    #[test]
+    fn test_life_grouping() {
+        let mode = Mode::Life {
+            dob: NaiveDate::from_ymd_opt(1990, 1, 1).unwrap(),
+            lifespan_years: 80,
+            age_rows: false,
+        };
+        assert_eq!(mode.name(), "life");
+        let mut grid = WeekGrid::calculate_at(&mode, today());
+
+        // Decades of rows and quarters of columns, without column captions
+        grid.apply_grouping(&Grouping {
+            rows: 10,
+            columns: 13,
+            captions: true,
+        });
+        assert_eq!((grid.row_group, grid.column_group), (10, 13));
+        assert!(grid.row_captions && !grid.column_captions);
+    }
+
+    #[test]
+    fn test_life_age_rows() {
+        let dob = NaiveDate::from_ymd_opt(1990, 5, 17).unwrap();
+        let mode = Mode::Life {
+            dob,
+            lifespan_years: 80,
+            age_rows: true,
+        };
+        let grid = WeekGrid::calculate_at(&mode, today());
+
+        assert_eq!(grid.rows, 80);
+        assert_eq!(grid.columns, 53);
+        for (age, &start) in grid.row_starts.iter().enumerate() {
+            let week = &grid.weeks[start];
+            assert_eq!(week.label, Some(age.to_string()));
+            let birthday = add_years(dob, age as i32);
+            assert!(week.start_date <= birthday && birthday <= week.end_date);
+        }
+        let lengths: Vec<_> = grid
+            .row_starts
+            .windows(2)
+            .map(|w| w[1] - w[0])
+            .chain([grid.total_weeks - grid.row_starts[79]])
+            .collect();
+        assert!(lengths.iter().all(|&len| len == 52 || len == 53));
+        // The last row stops short of the week of the 80th birthday
+        assert!(grid.weeks.last().unwrap().end_date < add_years(dob, 80));
+
+        assert_eq!(grid.row_label(33).as_deref(), Some("33"));
+        assert_eq!(grid.row_range(79).end, grid.total_weeks);
+
+        // Still 33 in March 2024, and so on row 33
+        let current = grid.current_week_index.unwrap();
+        assert_eq!(grid.cell_position(current).1, 33);
+
+        let days = WeekGrid::calculate_with(
+            &mode,
+            &GridOptions {
+                granularity: Granularity::Day,
+                as_of: Some(today()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(days.rows, 80);
+        assert_eq!(days.columns, 366);
+        assert_eq!(days.cell_position(days.current_week_index.unwrap()).1, 33);
+
+        // Rows can't start mid-year with coarser cells
+        let months = WeekGrid::calculate_with(
+            &mode,
+            &GridOptions {
+                granularity: Granularity::Month,
+                as_of: Some(today()),
+                ..Default::default()
+            },
+        );
+        assert!(months.row_starts.is_empty());
+        assert_eq!(months.columns, 12);
+
+        // Survival shading keeps adding age rows
+        let mut grid = grid;
+        let curve = crate::lifetable::life_table("US")
+            .unwrap()
+            .curve(crate::lifetable::Sex::Female);
+        let options = GridOptions {
+            as_of: Some(today()),
+            ..Default::default()
+        };
+        grid.apply_survival(dob, &curve, &options);
+        assert!(grid.rows > 80);
+        assert_eq!(grid.rows, grid.row_starts.len());
+        assert_eq!(grid.weeks[grid.row_starts[80]].label.as_deref(), Some("80"));
+    }
+
+    #[test]
+    fn test_year_end_mode() {
+        let grid = WeekGrid::calculate_at(&Mode::YearEnd, today());
+
+        // March 11 through December 30, 2024
+        assert_eq!(grid.total_weeks, 43);
+        assert_eq!(grid.current_week_index, Some(0));
+        assert_eq!(grid.title, "Weeks Remaining in 2024");
+        assert_eq!(grid.subtitle, "42 weeks to go");
+    }
+
+    #[test]
+    fn test_calculate_at() {
+        let mode = Mode::YearEnd;
+        let december = NaiveDate::from_ymd_opt(2024, 12, 20).unwrap();
+        assert_eq!(WeekGrid::calculate_at(&mode, december).total_weeks, 3);
+
+        let options = GridOptions {
+            as_of: Some(december),
+            ..Default::default()
+        };
+        assert_eq!(WeekGrid::calculate_with(&mode, &options).total_weeks, 3);
+    }
+
+    #[test]
+    fn test_full_year_mode() {
+        let grid = WeekGrid::calculate_at(&Mode::FullYear { month_rows: false }, today());
+
+        let first = grid.weeks.first().unwrap();
+        let last = grid.weeks.last().unwrap();
+        assert_eq!(
+            first.start_date,
+            NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()
+        );
+        assert_eq!(last.end_date, NaiveDate::from_ymd_opt(2025, 1, 5).unwrap());
+        assert_eq!(grid.total_weeks, 53);
+        assert_eq!(grid.current_week_index, Some(10));
+        assert_eq!(grid.elapsed_weeks, 10);
+        assert_eq!(grid.rows, 1);
+        assert_eq!(grid.subtitle, "Week 11 of 52 (18%)");
+
+        let grid = WeekGrid::calculate_at(&Mode::FullYear { month_rows: true }, today());
+        assert_eq!(grid.rows, 12);
+        assert!(grid.columns <= 6);
+        assert_eq!(grid.cell_position(0), (0, 0));
+        assert_eq!(grid.cell_position(grid.total_weeks - 1).1, 11);
+        assert_eq!(grid.weeks[0].label.as_deref(), Some("Jan"));
+        assert_eq!(grid.row_label(2).as_deref(), Some("Mar"));
+        assert_eq!(grid.row_range(12), grid.total_weeks..grid.total_weeks);
+    }
+
+    #[test]
+    fn test_full_year_subtitle() {
+        let subtitle = |date: NaiveDate, week_numbering, week_start| {
+            let options = GridOptions {
+                week_numbering,
+                week_start,
+                as_of: Some(date),
+                ..Default::default()
+            };
+            WeekGrid::calculate_with(&Mode::FullYear { month_rows: false }, &options).subtitle
+        };
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let (iso, us) = (WeekNumbering::Iso, WeekNumbering::Us);
+
+        assert_eq!(iso.weeks_in_year(2024, WeekStart::Monday), 52);
+        assert_eq!(iso.weeks_in_year(2026, WeekStart::Monday), 53);
+        assert_eq!(us.weeks_in_year(2024, WeekStart::Sunday), 52);
+
+        // March 13, 2024 is in ISO week 11 and US week 11
+        assert!(subtitle(today(), iso, WeekStart::Monday).starts_with("Week 11 of 52 "));
+        assert!(subtitle(today(), us, WeekStart::Sunday).starts_with("Week 11 of 52 "));
+        // The last days of 2024 fall in ISO week 1 of 2025
+        assert!(subtitle(date(2024, 12, 31), iso, WeekStart::Monday).starts_with("Week 1 of 52 "));
+        // January 1, 2027 falls in ISO week 53 of 2026
+        assert!(subtitle(date(2027, 1, 1), iso, WeekStart::Monday).starts_with("Week 53 of 53 "));
+    }
+
+    #[test]
+    fn test_life_granularity() {
+        let mode = Mode::Life {
+            dob: NaiveDate::from_ymd_opt(1990, 5, 17).unwrap(),
+            lifespan_years: 80,
+            age_rows: false,
+        };
+
+        let months = WeekGrid::calculate_with(
+            &mode,
+            &GridOptions {
+                granularity: Granularity::Month,
+                as_of: Some(today()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(months.total_weeks, 80 * 12 + 1);
+        assert_eq!(months.columns, 12);
+        assert_eq!(
+            months.weeks[0].start_date,
+            NaiveDate::from_ymd_opt(1990, 5, 1).unwrap()
+        );
+        assert_eq!(
+            months.weeks[0].end_date,
+            NaiveDate::from_ymd_opt(1990, 5, 31).unwrap()
+        );
+        assert!(months.title.starts_with("Life in Months"));
+
+        let years = WeekGrid::calculate_with(
+            &mode,
+            &GridOptions {
+                granularity: Granularity::Year,
+                as_of: Some(today()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(years.total_weeks, 81);
+        assert_eq!(years.columns, 10);
+    }
+
+    #[test]
+    fn test_periods_between() {
+        let from = NaiveDate::from_ymd_opt(2024, 1, 31).unwrap();
+        let to = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let monday = WeekStart::Monday;
+        assert_eq!(Granularity::Day.periods_between(from, to, monday), 395);
+        assert_eq!(Granularity::Week.periods_between(from, to, monday), 56);
+        assert_eq!(Granularity::Month.periods_between(from, to, monday), 14);
+        assert_eq!(Granularity::Year.periods_between(from, to, monday), 1);
+        assert!(Granularity::parse("fortnight").is_err());
+    }
+
+    #[test]
+    fn test_range_mode() {
+        let params = ModeParams {
+            start: NaiveDate::from_ymd_opt(2024, 1, 3),
+            end: NaiveDate::from_ymd_opt(2024, 3, 27),
+            title: Some("Sabbatical".to_string()),
+            ..Default::default()
+        };
+        let mode = Mode::from_str_with_params("range", &params).unwrap();
+        let grid = WeekGrid::calculate_at(&mode, today());
+
+        // Jan 1 (Monday of the start week) through Mar 25 (Monday of the end week)
+        assert_eq!(grid.total_weeks, 13);
+        assert_eq!(grid.title, "Sabbatical");
+        assert_eq!(
+            grid.weeks[0].start_date,
+            NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()
+        );
+
+        let reversed = ModeParams {
+            start: params.end,
+            end: params.start,
+            ..Default::default()
+        };
+        assert!(Mode::from_str_with_params("range", &reversed).is_err());
+        assert!(Mode::from_str_with_params("range", &ModeParams::default()).is_err());
+    }
+
+    #[test]
+    fn test_countdown_mode() {
+        let today = today();
+        let params = ModeParams {
+            targets: vec![
+                CountdownTarget {
+                    name: "Marathon".to_string(),
+                    date: today + chrono::Duration::weeks(12),
+                    color: None,
+                },
+                CountdownTarget {
+                    name: "Release".to_string(),
+                    date: today + chrono::Duration::weeks(5),
+                    color: None,
+                },
+                CountdownTarget {
+                    name: "Old deadline".to_string(),
+                    date: today - chrono::Duration::weeks(3),
+                    color: None,
+                },
+            ],
+            ..Default::default()
+        };
+        let grid = WeekGrid::calculate_at(
+            &Mode::from_str_with_params("countdown", &params).unwrap(),
+            today,
+        );
+
+        assert_eq!(grid.total_weeks, 13);
+        assert_eq!(grid.current_week_index, Some(0));
+        assert_eq!(grid.weeks[5].event, Some(0));
+        assert_eq!(grid.weeks[12].event, Some(1));
+        assert_eq!(
+            grid.subtitle,
+            "Old deadline - passed\nRelease - 5 weeks left\nMarathon - 12 weeks left"
+        );
+
+        assert!(Mode::from_str_with_params("countdown", &ModeParams::default()).is_err());
+    }
+
+    #[test]
+    fn test_tail_end_mode() {
+        let item = |name: &str, per_year, since, until| RecurringItem {
+            name: name.to_string(),
+            per_year,
+            since,
+            until,
+            color: None,
+        };
+        let params = ModeParams {
+            dob: NaiveDate::from_ymd_opt(1990, 1, 1),
+            lifespan: Some(80),
+            recurring: vec![
+                item("Summers", 1.0, None, None),
+                item(
+                    "Visits",
+                    3.0,
+                    NaiveDate::from_ymd_opt(2020, 1, 1),
+                    NaiveDate::from_ymd_opt(2040, 1, 1),
+                ),
+            ],
+            ..Default::default()
+        };
+        let mode = Mode::from_str_with_params("tail-end", &params).unwrap();
+        let grid = WeekGrid::calculate_at(&mode, today());
+
+        assert_eq!(grid.total_weeks, 140);
+        assert_eq!(grid.elapsed_weeks, 34 + 12);
+        assert_eq!(
+            grid.subtitle,
+            "Summers - 46 left of 80 (1/year)\nVisits - 48 left of 60 (3/year)"
+        );
+
+        // Each item starts on its own row, past cells in the item's color
+        let visits = grid
+            .weeks
+            .iter()
+            .position(|w| w.label.as_deref() == Some("Visits"))
+            .unwrap();
+        assert_eq!(visits, 80);
+        assert_eq!(grid.cell_position(visits).0, 0);
+        assert_eq!(grid.weeks[visits].chapter, Some(1));
+        assert_eq!(grid.weeks[visits + 12].chapter, None);
+        assert_eq!(grid.weeks[visits + 12].status, WeekStatus::Future);
+    }
+
+    #[test]
+    fn test_tail_end_ignores_dated_overlays() {
+        let params = ModeParams {
+            dob: NaiveDate::from_ymd_opt(1990, 1, 1),
+            lifespan: Some(80),
+            recurring: vec![RecurringItem {
+                name: "Summers".to_string(),
+                per_year: 1.0,
+                since: None,
+                until: None,
+                color: None,
+            }],
+            ..Default::default()
+        };
+        let mode = Mode::from_str_with_params("tail-end", &params).unwrap();
+        let options = GridOptions {
+            as_of: Some(today()),
+            ..Default::default()
+        };
+        let mut grid = WeekGrid::calculate_with(&mode, &options);
+        let config = Config {
+            events: vec![LifeEvent {
+                name: "Wedding".to_string(),
+                date: NaiveDate::from_ymd_opt(2018, 6, 9).unwrap(),
+                color: None,
+            }],
+            chapters: vec![Chapter {
+                name: "School".to_string(),
+                start: NaiveDate::from_ymd_opt(1996, 9, 1).unwrap(),
+                end: NaiveDate::from_ymd_opt(2008, 6, 30).unwrap(),
+                color: None,
+            }],
+            ..Default::default()
+        };
+        grid.apply_overlays(&mode, &config, &Journal::default(), None, &options)
+            .unwrap();
+
+        // Cells count summers, not calendar weeks, so nothing is marked by date
+        assert!(grid.legend.is_empty());
+        assert!(grid.weeks.iter().all(|w| w.event.is_none()));
+        assert_eq!(grid.chapters.len(), 1);
+        assert_eq!(grid.weeks[0].chapter, Some(0));
+    }
+
+    #[test]
+    fn test_retirement_mode() {
+        let params = ModeParams {
+            dob: NaiveDate::from_ymd_opt(1990, 1, 1),
+            vacation_weeks: Some(5),
+            holiday_days: Some(10),
+            ..Default::default()
+        };
+        let mode = Mode::from_str_with_params("retirement", &params).unwrap();
+        let grid = WeekGrid::calculate_at(&mode, today());
+
+        assert_eq!(grid.columns, 52);
+        assert_eq!(
+            grid.subtitle,
+            "1391 working weeks remaining (1607 weeks, less 7.0 off per year)\n\
+             Retiring 2055-01-01 at age 65"
+        );
+
+        // Seven free weeks a year, spread through the future weeks
+        let free = grid
+            .weeks
+            .iter()
+            .filter(|w| w.category == WeekCategory::Free)
+            .count();
+        assert_eq!(free, 1607 - 1391);
+        assert!(grid.weeks[..4]
+            .iter()
+            .all(|w| w.category == WeekCategory::Regular));
+        assert_eq!(grid.weeks[4].category, WeekCategory::Free);
+
+        assert!(Mode::from_str_with_params("retirement", &ModeParams::default()).is_err());
+    }
+
+    #[test]
+    fn test_term_mode() {
+        let date = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+        let period = |name: &str, start, end, exam| TermBreak {
+            name: name.to_string(),
+            start,
+            end,
+            exam,
+        };
+        let terms = vec![
+            Term {
+                name: "Spring 2024".to_string(),
+                start: date(1, 8),
+                end: date(4, 5),
+                breaks: vec![
+                    period("Reading week", date(2, 10), date(2, 18), false),
+                    period("Exams", date(4, 1), date(4, 5), true),
+                ],
+            },
+            Term {
+                name: "Autumn 2023".to_string(),
+                start: NaiveDate::from_ymd_opt(2023, 9, 25).unwrap(),
+                end: NaiveDate::from_ymd_opt(2023, 12, 8).unwrap(),
+                breaks: Vec::new(),
+            },
+        ];
+        let params = ModeParams {
+            terms,
+            ..Default::default()
+        };
+        let mode = Mode::from_str_with_params("term", &params).unwrap();
+        let grid = WeekGrid::calculate_at(&mode, today());
+
+        assert_eq!(grid.title, "Spring 2024");
+        assert_eq!(grid.total_weeks, 13);
+        // The break only clips the weekend before its week
+        assert_eq!(grid.weeks[4].category, WeekCategory::Regular);
+        assert_eq!(grid.weeks[5].category, WeekCategory::Break);
+        assert_eq!(grid.weeks[12].category, WeekCategory::Exam);
+        assert_eq!(grid.subtitle, "2 teaching weeks left");
+    }
+
+    #[test]
+    fn test_term_mode_without_terms() {
+        assert!(Mode::from_str_with_params("term", &ModeParams::default()).is_err());
+
+        // A mode deserialized with an empty list yields an empty grid
+        let grid = WeekGrid::calculate_at(&Mode::Term { terms: Vec::new() }, today());
+        assert!(grid.weeks.is_empty());
+        assert_eq!((grid.columns, grid.rows), (0, 0));
+        assert_eq!(grid.subtitle, "No terms configured");
+    }
+
+    #[test]
+    fn test_fiscal_mode() {
+        let grid = WeekGrid::calculate_at(&Mode::Fiscal { start_month: 7 }, today());
+        assert_eq!(grid.title, "FY2024 in Weeks");
+        assert_eq!(
+            grid.weeks[0].start_date,
+            NaiveDate::from_ymd_opt(2023, 6, 26).unwrap()
+        );
+        assert_eq!(grid.rows, 4);
+        assert_eq!(grid.weeks[grid.row_starts[2]].label.as_deref(), Some("Q3"));
+
+        // Q3 runs Jan 1 - Mar 31; the week of Mar 25 is its last
+        assert_eq!(grid.subtitle, "2 weeks left in Q3 / 15 left in FY2024");
+        assert_eq!(grid.chapters[0].label, "Q3 FY2024");
+        let current = grid.current_week_index.unwrap();
+        assert_eq!(grid.weeks[current].chapter, Some(0));
+        assert_eq!(grid.weeks[grid.row_starts[1]].chapter, None);
+
+        let params = ModeParams {
+            fiscal_start_month: Some(13),
+            ..Default::default()
+        };
+        assert!(Mode::from_str_with_params("fiscal", &params).is_err());
+    }
+
+    #[test]
+    fn test_fiscal_mode_out_of_range_month() {
+        // Months outside 1-12 that skipped validation are clamped
+        let january = WeekGrid::calculate_at(&Mode::Fiscal { start_month: 0 }, today());
+        assert_eq!(january.title, "FY2024 in Weeks");
+        assert_eq!(
+            january.weeks[0].start_date,
+            NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()
+        );
+        let december = WeekGrid::calculate_at(&Mode::Fiscal { start_month: 13 }, today());
+        assert_eq!(
+            december.weeks[0].start_date,
+            NaiveDate::from_ymd_opt(2023, 11, 27).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_sprint_mode() {
+        let params = ModeParams {
+            sprint_anchor: NaiveDate::from_ymd_opt(2024, 1, 3),
+            release: NaiveDate::from_ymd_opt(2024, 6, 28),
+            sprint_weeks: Some(3),
+            ..Default::default()
+        };
+        let mode = Mode::from_str_with_params("sprint", &params).unwrap();
+        let grid = WeekGrid::calculate_at(&mode, today());
+
+        // Weeks run Wednesday to Tuesday from the anchor; March 13 starts week 11
+        assert_eq!(grid.total_weeks, 27);
+        assert_eq!(grid.column_group, 3);
+        assert_eq!(grid.columns % 3, 0);
+        assert_eq!(grid.current_week_index, Some(10));
+        assert_eq!(grid.weeks[9].label.as_deref(), Some("S4"));
+        assert_eq!(
+            grid.subtitle,
+            "Sprint 4 of 9, week 2 of 3 - 15 weeks to release"
+        );
+        assert_eq!(grid.chapters[0].label, "Sprint 4");
+        assert_eq!(grid.weeks[11].chapter, Some(0));
+        assert_eq!(grid.weeks[12].chapter, None);
+        assert_eq!(grid.weeks[25].event, Some(0));
+
+        let params = ModeParams {
+            release: NaiveDate::from_ymd_opt(2023, 12, 1),
+            ..params
+        };
+        assert!(Mode::from_str_with_params("sprint", &params).is_err());
+    }
+
+    #[test]
+    fn test_sprint_mode_zero_length() {
+        let date = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+        // Modes deserialized without validation yield empty grids
+        let grid = WeekGrid::calculate_at(
+            &Mode::Sprint {
+                anchor: date(1, 3),
+                release: date(6, 28),
+                sprint_weeks: 0,
+            },
+            today(),
+        );
+        assert!(grid.weeks.is_empty());
+        assert_eq!(grid.subtitle, "Sprints must be at least one week long");
+    }
+
+    #[test]
+    fn test_sprint_mode_release_before_anchor() {
+        let date = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+        let grid = WeekGrid::calculate_at(
+            &Mode::Sprint {
+                anchor: date(6, 28),
+                release: date(1, 3),
+                sprint_weeks: 2,
+            },
+            today(),
+        );
+        assert!(grid.weeks.is_empty());
+        assert_eq!((grid.columns, grid.rows), (0, 0));
+        assert_eq!(
+            grid.subtitle,
+            "Release 2024-01-03 is before sprint anchor 2024-06-28"
+        );
+    }
+
+    #[test]
+    fn test_sprint_grouping() {
+        let params = ModeParams {
+            sprint_anchor: NaiveDate::from_ymd_opt(2024, 1, 3),
+            release: NaiveDate::from_ymd_opt(2024, 6, 28),
+            sprint_weeks: Some(3),
+            ..Default::default()
+        };
+        let mode = Mode::from_str_with_params("sprint", &params).unwrap();
+        assert_eq!(mode.name(), "sprint");
+        let mut grid = WeekGrid::calculate_at(&mode, today());
+
+        // Configured grouping adds row blocks but keeps the captioned sprints
+        grid.apply_grouping(&Grouping {
+            rows: 2,
+            ..Default::default()
+        });
+        assert_eq!((grid.row_group, grid.column_group), (2, 3));
+        assert!(grid.column_captions && !grid.row_captions);
+    }
+
+    #[test]
+    fn test_pregnancy_mode() {
+        let params = ModeParams {
+            conceived: NaiveDate::from_ymd_opt(2023, 11, 1),
+            ..Default::default()
+        };
+        let mode = Mode::from_str_with_params("pregnancy", &params).unwrap();
+        let due = NaiveDate::from_ymd_opt(2024, 7, 24).unwrap();
+        assert!(matches!(mode, Mode::Pregnancy { due: d, born: None } if d == due));
+
+        // 147 days after the last menstrual period: week 22
+        let grid = WeekGrid::calculate_at(&mode, today());
+        assert_eq!(grid.total_weeks, 40);
+        assert_eq!((grid.columns, grid.rows), (10, 4));
+        assert_eq!(grid.current_week_index, Some(21));
+        assert_eq!(grid.subtitle, "Week 22, 18 to go");
+        assert_eq!(grid.chapters.len(), 3);
+        assert_eq!(grid.weeks[12].chapter, Some(0));
+        assert_eq!(grid.weeks[13].chapter, Some(1));
+        assert_eq!(grid.weeks[39].chapter, Some(2));
+
+        // After birth the grid switches to the first year
+        let born = NaiveDate::from_ymd_opt(2024, 1, 10).unwrap();
+        let grid = WeekGrid::calculate_at(
+            &Mode::Pregnancy {
+                due,
+                born: Some(born),
+            },
+            today(),
+        );
+        assert_eq!(grid.title, "Baby's First Year");
+        assert_eq!(grid.total_weeks, 52);
+        assert_eq!(grid.weeks[0].start_date, born);
+        assert_eq!(grid.subtitle, "Week 10, 42 to go");
+    }
+
+    #[test]
+    fn test_family_mode() {
+        let person = |name: &str, dob: NaiveDate, lifespan_years: Option<u8>| Person {
+            name: name.to_string(),
+            dob,
+            lifespan_years,
+            color: None,
+        };
+        let params = ModeParams {
+            people: vec![
+                person("Sam", NaiveDate::from_ymd_opt(1990, 5, 17).unwrap(), None),
+                person(
+                    "Kid",
+                    NaiveDate::from_ymd_opt(2020, 2, 1).unwrap(),
+                    Some(90),
+                ),
+            ],
+            lifespan: Some(80),
+            ..Default::default()
+        };
+        let mode = Mode::from_str_with_params("family", &params).unwrap();
+        let grid = WeekGrid::calculate_at(&mode, today());
+
+        // 1990 through 2110, one row per calendar year
+        assert_eq!(grid.rows, 2110 - 1990 + 1);
+        assert!((52..=53).contains(&grid.columns));
+        assert_eq!(grid.weeks[0].label.as_deref(), Some("1990"));
+        assert_eq!(grid.people.len(), 2);
+
+        // Both are alive today, and the current week sits in the 2024 row
+        let current = grid.current_week_index.unwrap();
+        assert_eq!(grid.weeks[current].people, vec![0, 1]);
+        assert_eq!(grid.cell_position(current).1, 2024 - 1990);
+        assert!(grid.weeks[0].people.is_empty());
+        let kid_last = grid
+            .week_index_of(NaiveDate::from_ymd_opt(2110, 1, 31).unwrap())
+            .unwrap();
+        assert_eq!(grid.weeks[kid_last].people, vec![1]);
+        assert!(grid.weeks.last().unwrap().people.is_empty());
+        assert!(grid.subtitle.starts_with("Sam (age 33) - "));
+
+        assert!(Mode::from_str_with_params("family", &ModeParams::default()).is_err());
+    }
+
+    #[test]
+    fn test_apply_journal() {
+        let date = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+        let mut journal = Journal::default();
+        journal.add(date(3, 4), "Shipped v1", Some(4)).unwrap();
+        journal.add(date(3, 11), "This week", Some(2)).unwrap();
+        journal.add(date(3, 18), "Next week", Some(5)).unwrap();
+
+        let mut grid = WeekGrid::calculate_at(&Mode::FullYear { month_rows: false }, today());
+        grid.apply_journal(&journal);
+        let rating_of = |d| grid.weeks[grid.week_index_of(d).unwrap()].rating;
+        assert_eq!(rating_of(date(3, 6)), Some(4));
+        assert_eq!(rating_of(date(3, 13)), Some(2));
+        // Future weeks can't be rated yet
+        assert_eq!(rating_of(date(3, 20)), None);
+        assert_eq!(rating_of(date(2, 26)), None);
+    }
+
+    #[test]
+    fn test_apply_data() {
+        let date = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+        let data = DataSeries::new(
+            "km".to_string(),
+            vec![
+                (date(2, 14), 1.0),
+                (date(3, 5), 5.0),
+                (date(3, 7), 7.5),
+                (date(3, 12), 3.0),
+                (date(3, 20), 10.0),
+            ],
+        );
+
+        let mut grid = WeekGrid::calculate_at(&Mode::FullYear { month_rows: false }, today());
+        grid.apply_data(&data);
+        let value_of = |d| grid.weeks[grid.week_index_of(d).unwrap()].value;
+        assert_eq!(grid.value_label.as_deref(), Some("km"));
+        assert_eq!(value_of(date(3, 4)), Some(12.5));
+        assert_eq!(value_of(date(3, 13)), Some(3.0));
+        // Quiet weeks after the first value count as zero
+        assert_eq!(value_of(date(2, 26)), Some(0.0));
+        assert_eq!(value_of(date(2, 5)), None);
+        // Values recorded ahead of time aren't shown
+        assert_eq!(value_of(date(3, 20)), None);
+    }
+
+    #[test]
+    fn test_apply_events() {
+        let dob = NaiveDate::from_ymd_opt(1990, 1, 1).unwrap();
+        let mut grid = WeekGrid::calculate_at(
+            &Mode::Life {
+                dob,
+                lifespan_years: 80,
+                age_rows: false,
+            },
+            today(),
+        );
+        let wedding = NaiveDate::from_ymd_opt(2015, 6, 13).unwrap();
+        grid.apply_events(&[
+            LifeEvent {
+                name: "Wedding".to_string(),
+                date: wedding,
+                color: Some("#FFD700".to_string()),
+            },
+            LifeEvent {
+                name: "Before birth".to_string(),
+                date: NaiveDate::from_ymd_opt(1980, 1, 1).unwrap(),
+                color: None,
+            },
+        ]);
+
+        assert_eq!(grid.legend.len(), 1);
+        assert_eq!(grid.legend[0].label, "Wedding");
+
+        let index = grid.week_index_of(wedding).unwrap();
+        assert_eq!(
+            grid.weeks[index].start_date,
+            week_start(wedding, WeekStart::Monday)
+        );
+        assert_eq!(grid.weeks[index].event, Some(0));
+        assert_eq!(grid.weeks.iter().filter(|w| w.event.is_some()).count(), 1);
+    }
+
+    #[test]
+    fn test_apply_calendars() {
+        let date = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+        let calendar = Calendar::parse(
+            "home",
+            "BEGIN:VCALENDAR
+BEGIN:VEVENT
+SUMMARY:Ski trip
+CATEGORIES:Vacation
+DTSTART;VALUE=DATE:20240212
+DTEND;VALUE=DATE:20240220
+END:VEVENT
+BEGIN:VEVENT
+SUMMARY:Book club
+DTSTART:20240104T190000
+DTEND:20240104T210000
+RRULE:FREQ=MONTHLY;COUNT=3
+END:VEVENT
+BEGIN:VEVENT
+SUMMARY:Anniversary dinner
+CATEGORIES:Birthday
+DTSTART;VALUE=DATE:20240618
+END:VEVENT
+BEGIN:VEVENT
+SUMMARY:Next year
+CATEGORIES:Later
+DTSTART;VALUE=DATE:20250210
+END:VEVENT
+END:VCALENDAR",
+        )
+        .unwrap();
+
+        let mut grid = WeekGrid::calculate_at(&Mode::FullYear { month_rows: false }, today());
+        grid.apply_events(&[LifeEvent {
+            name: "Birthday".to_string(),
+            date: date(3, 4),
+            color: None,
+        }]);
+        let colors = BTreeMap::from([("vacation".to_string(), "#88C0D0".to_string())]);
+        grid.apply_calendars(&[calendar], &colors);
+
+        // A category named like a life event gets its own entry; events
+        // after the grid ends add none
+        let labels: Vec<_> = grid.legend.iter().map(|e| e.label.as_str()).collect();
+        assert_eq!(labels, ["Birthday", "Vacation", "home", "Birthday"]);
+        assert_eq!(grid.legend[1].color.as_deref(), Some("#88C0D0"));
+        assert_eq!(grid.legend[2].color, None);
+
+        let event_of = |d| grid.weeks[grid.week_index_of(d).unwrap()].event;
+        // A trip spanning a weekend touches two weeks
+        assert_eq!(event_of(date(2, 12)), Some(1));
+        assert_eq!(event_of(date(2, 19)), Some(1));
+        assert_eq!(event_of(date(2, 26)), None);
+        assert_eq!(event_of(date(1, 4)), Some(2));
+        assert_eq!(event_of(date(2, 4)), Some(2));
+        // Life events keep their week
+        assert_eq!(event_of(date(3, 4)), Some(0));
+        assert_eq!(event_of(date(4, 4)), None);
+        assert_eq!(event_of(date(6, 18)), Some(3));
+    }
+
+    #[test]
+    fn test_apply_survival() {
+        let dob = NaiveDate::from_ymd_opt(1990, 1, 1).unwrap();
+        let options = GridOptions {
+            as_of: Some(today()),
+            ..Default::default()
+        };
+        let mut grid = WeekGrid::calculate_with(
+            &Mode::Life {
+                dob,
+                lifespan_years: 80,
+                age_rows: false,
+            },
+            &options,
+        );
+        let lifespan_weeks = grid.weeks.len();
+        let curve = crate::lifetable::life_table("US")
+            .unwrap()
+            .curve(crate::lifetable::Sex::Female);
+        grid.apply_survival(dob, &curve, &options);
+
+        // The grid runs on past the expected lifespan
+        assert!(grid.weeks.len() > lifespan_weeks);
+        assert_eq!(grid.total_weeks, lifespan_weeks);
+        assert_eq!(grid.rows, grid.weeks.len().div_ceil(grid.columns));
+
+        let current = grid.current_week_index.unwrap();
+        assert_eq!(grid.weeks[current].survival, None);
+        let next = grid.weeks[current + 1].survival.unwrap();
+        let last = grid.weeks.last().unwrap().survival.unwrap();
+        assert!(next > 0.99);
+        assert!((MIN_SURVIVAL * 0.9..0.05).contains(&last));
+        assert!(grid.weeks[current + 1..]
+            .windows(2)
+            .all(|w| w[0].survival >= w[1].survival));
+    }
+
+    #[test]
+    fn test_apply_chapters() {
+        let dob = NaiveDate::from_ymd_opt(1990, 1, 1).unwrap();
+        let mut grid = WeekGrid::calculate_at(
+            &Mode::Life {
+                dob,
+                lifespan_years: 80,
+                age_rows: false,
+            },
+            today(),
+        );
+        grid.apply_chapters(&[
+            Chapter {
+                name: "School".to_string(),
+                start: NaiveDate::from_ymd_opt(1996, 9, 1).unwrap(),
+                end: NaiveDate::from_ymd_opt(2008, 6, 30).unwrap(),
+                color: None,
+            },
+            Chapter {
+                name: "University".to_string(),
+                start: NaiveDate::from_ymd_opt(2008, 6, 30).unwrap(),
+                end: NaiveDate::from_ymd_opt(2012, 6, 30).unwrap(),
+                color: None,
+            },
+            Chapter {
+                name: "Before birth".to_string(),
+                start: NaiveDate::from_ymd_opt(1980, 1, 1).unwrap(),
+                end: NaiveDate::from_ymd_opt(1981, 1, 1).unwrap(),
+                color: None,
+            },
+        ]);
+
+        assert_eq!(grid.chapters.len(), 2);
+        let week_of = |y, m, d| {
+            let index = grid
+                .week_index_of(NaiveDate::from_ymd_opt(y, m, d).unwrap())
+                .unwrap();
+            grid.weeks[index].chapter
+        };
+        assert_eq!(week_of(1995, 1, 1), None);
+        assert_eq!(week_of(2000, 1, 1), Some(0));
+        // The boundary week belongs to the first matching chapter
+        assert_eq!(week_of(2008, 6, 30), Some(0));
+        assert_eq!(week_of(2010, 1, 1), Some(1));
+        assert_eq!(week_of(2013, 1, 1), None);
     }
 }
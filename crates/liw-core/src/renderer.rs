@@ -2,9 +2,11 @@
 //!
 //! Generates wallpaper images with the week grid visualization.
 
-use crate::config::Theme;
-use crate::modes::{WeekGrid, WeekStatus};
+use crate::config::{parse_hex_color, Theme};
+use crate::modes::{LegendEntry, WeekCategory, WeekGrid, WeekStatus};
+use chrono::{Local, NaiveDate};
 use image::{DynamicImage, ImageBuffer, Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
 use std::path::Path;
 
 /// Padding around the grid (percentage of screen size)
@@ -13,63 +15,474 @@ const PADDING_PERCENT: f32 = 0.08;
 const GAP_PERCENT: f32 = 0.15;
 /// Corner radius for cells (percentage of cell size)
 const CORNER_RADIUS_PERCENT: f32 = 0.2;
+/// Opacity of chapter colors on future weeks
+const FUTURE_CHAPTER_OPACITY: f32 = 0.35;
+/// Height of the legend strip (percentage of screen height)
+const LEGEND_HEIGHT_PERCENT: f32 = 0.04;
+/// How far free weeks are tinted toward the accent color
+const FREE_WEEK_TINT: f32 = 0.45;
+/// Extra space between column groups (in cells)
+const GROUP_GAP_CELLS: f32 = 0.5;
+/// Height of the caption above each row of column groups (in cells)
+const GROUP_LABEL_CELLS: f32 = 0.5;
+/// Font size of row and column labels (percentage of screen height)
+const AXIS_LABEL_PERCENT: f32 = 0.016;
+
+/// Number of swatches in the heatmap legend
+const SCALE_STEPS: usize = 5;
+
+/// Colors for journal ratings 1 (bad) to 5 (great)
+const RATING_COLORS: [[u8; 4]; 5] = [
+    [215, 70, 70, 255],
+    [235, 140, 70, 255],
+    [225, 200, 90, 255],
+    [150, 205, 100, 255],
+    [70, 185, 110, 255],
+];
+
+/// Fallback colors for legend entries without an explicit color
+const EVENT_PALETTE: [[u8; 4]; 6] = [
+    [255, 200, 60, 255],
+    [80, 200, 220, 255],
+    [190, 120, 255, 255],
+    [255, 110, 180, 255],
+    [120, 220, 120, 255],
+    [255, 150, 70, 255],
+];
+
+/// How the current cell shows progress through its period
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CurrentFill {
+    /// Fill the whole cell
+    #[default]
+    Solid,
+    /// Fill from the left edge, one slice per elapsed day
+    Horizontal,
+    /// Sweep clockwise from twelve o'clock, like a pie chart
+    Radial,
+}
+
+impl CurrentFill {
+    /// Parse a fill style from string
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.to_lowercase().as_str() {
+            "solid" | "full" => Ok(CurrentFill::Solid),
+            "horizontal" | "bar" => Ok(CurrentFill::Horizontal),
+            "radial" | "pie" => Ok(CurrentFill::Radial),
+            _ => Err(format!(
+                "Unknown current week fill: {}. Options: solid, horizontal, radial",
+                value
+            )),
+        }
+    }
+}
+
+/// Optional decorations drawn around the grid
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenderOptions {
+    /// Label rows in the left margin with their year, age or month
+    pub row_labels: bool,
+    /// Label every this many rows, starting with the first
+    pub row_label_every: usize,
+    /// Number columns along the top: 1, then every `column_label_every`
+    pub column_labels: bool,
+    /// Spacing of column numbers
+    pub column_label_every: usize,
+    /// How much of the current cell to fill
+    pub current_fill: CurrentFill,
+    /// Reference date for the partial fill (defaults to today)
+    pub as_of: Option<NaiveDate>,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            row_labels: false,
+            row_label_every: 10,
+            column_labels: false,
+            column_label_every: 10,
+            current_fill: CurrentFill::Solid,
+            as_of: None,
+        }
+    }
+}
 
 /// Render the week grid to an image
 pub fn render_grid(grid: &WeekGrid, theme: &Theme, width: u32, height: u32) -> DynamicImage {
+    render_grid_with(grid, theme, width, height, &RenderOptions::default())
+}
+
+/// Render the week grid to an image with row and column labels as configured
+pub fn render_grid_with(
+    grid: &WeekGrid,
+    theme: &Theme,
+    width: u32,
+    height: u32,
+    options: &RenderOptions,
+) -> DynamicImage {
     let colors = theme.colors();
+    let today = options.as_of.unwrap_or_else(|| Local::now().date_naive());
     let mut img: RgbaImage = ImageBuffer::from_pixel(width, height, Rgba(colors.background));
 
     // Calculate layout
     let padding_x = (width as f32 * PADDING_PERCENT) as u32;
     let padding_y = (height as f32 * PADDING_PERCENT) as u32;
 
-    // Reserve space for title and subtitle
+    // Reserve space for title and subtitle (one slot per subtitle line)
+    let subtitle_lines: Vec<&str> = grid.subtitle.lines().collect();
     let title_height = (height as f32 * 0.06) as u32;
     let subtitle_height = (height as f32 * 0.03) as u32;
-    let header_height = title_height + subtitle_height + padding_y / 2;
+    let header_height =
+        title_height + subtitle_height * subtitle_lines.len().max(1) as u32 + padding_y / 2;
+
+    // Resolve legend colors once; people, then chapters, take the first palette slots
+    let person_colors: Vec<[u8; 4]> = grid
+        .people
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| legend_color(entry, i))
+        .collect();
+    let chapter_colors: Vec<[u8; 4]> = grid
+        .chapters
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| legend_color(entry, grid.people.len() + i))
+        .collect();
+    let event_colors: Vec<[u8; 4]> = grid
+        .legend
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| legend_color(entry, grid.people.len() + grid.chapters.len() + i))
+        .collect();
+
+    // Heatmap values of past weeks are scaled from zero (or the smallest
+    // negative value) up to the largest shown
+    let (value_min, value_max) = grid
+        .weeks
+        .iter()
+        .filter(|w| w.status == WeekStatus::Past)
+        .filter_map(|w| w.value)
+        .fold((0.0, f64::NEG_INFINITY), |(min, max): (f64, f64), v| {
+            (min.min(v), max.max(v))
+        });
+    let value_color = |value: f64| {
+        let t = match value_max - value_min {
+            span if span > 0.0 => (value - value_min) / span,
+            _ => 0.0,
+        };
+        blend(colors.scale_low, colors.scale_high, t as f32)
+    };
+    let scale_steps = if value_max > value_min {
+        SCALE_STEPS
+    } else {
+        1
+    };
+    let scale_entries: Vec<(String, [u8; 4])> = match &grid.value_label {
+        Some(label) if value_min <= value_max => (0..scale_steps)
+            .map(|step| {
+                let value =
+                    value_min + (value_max - value_min) * step as f64 / (SCALE_STEPS - 1) as f64;
+                let text = match step {
+                    0 => format!("{}: {}", label, format_value(value)),
+                    _ => format_value(value),
+                };
+                (text, value_color(value))
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let legend: Vec<(&str, [u8; 4])> = grid
+        .people
+        .iter()
+        .chain(&grid.chapters)
+        .chain(&grid.legend)
+        .map(|entry| entry.label.as_str())
+        .zip(
+            person_colors
+                .iter()
+                .chain(&chapter_colors)
+                .chain(&event_colors)
+                .copied(),
+        )
+        .chain(
+            scale_entries
+                .iter()
+                .map(|(text, color)| (text.as_str(), *color)),
+        )
+        .collect();
 
-    let grid_width = width - 2 * padding_x;
-    let grid_height = height - 2 * padding_y - header_height;
+    // Reserve space for the legend below the grid
+    let legend_height = if legend.is_empty() {
+        0
+    } else {
+        (height as f32 * LEGEND_HEIGHT_PERCENT) as u32
+    };
+
+    // Reserve a left margin wide enough for the longest row label and a strip
+    // above the grid for column numbers
+    let label_size = (height as f32 * AXIS_LABEL_PERCENT) as u32;
+    let char_width = (label_size as f32 * 0.6) as u32;
+    let row_labels: Vec<(usize, String)> = match options.row_label_every {
+        every if options.row_labels && every > 0 => (0..grid.rows)
+            .step_by(every)
+            .filter_map(|row| grid.row_label(row).map(|label| (row, label)))
+            .collect(),
+        _ => Vec::new(),
+    };
+    let row_label_width = match row_labels.iter().map(|(_, l)| l.chars().count()).max() {
+        Some(chars) => char_width * chars as u32 + label_size,
+        None => 0,
+    };
+    let column_labels: Vec<usize> = match options.column_label_every {
+        every if options.column_labels && every > 0 && grid.columns > 1 => (0..grid.columns)
+            .filter(|&col| col == 0 || (col + 1) % every == 0)
+            .collect(),
+        _ => Vec::new(),
+    };
+    let column_label_height = if column_labels.is_empty() {
+        0
+    } else {
+        label_size * 3 / 2
+    };
+
+    // Blocks of rows are captioned in a right margin, like "1990-1999"
+    let row_captions: Vec<(usize, usize, String)> = match grid.row_group {
+        group if grid.row_captions && group > 0 => (0..grid.rows)
+            .step_by(group)
+            .filter_map(|first| {
+                let last = (first + group).min(grid.rows) - 1;
+                match (grid.row_label(first), grid.row_label(last)) {
+                    (Some(from), Some(to)) if from != to => {
+                        Some((first, last, format!("{}-{}", from, to)))
+                    }
+                    (Some(from), _) => Some((first, last, from)),
+                    (None, _) => None,
+                }
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    let caption_width = match row_captions.iter().map(|(_, _, c)| c.chars().count()).max() {
+        Some(chars) => char_width * chars as u32 + label_size,
+        None => 0,
+    };
+
+    // Labels can outgrow a narrow screen; the grid then shrinks to nothing
+    // rather than wrapping around
+    let grid_width = width.saturating_sub(2 * padding_x + row_label_width + caption_width);
+    let grid_height =
+        height.saturating_sub(2 * padding_y + header_height + legend_height + column_label_height);
+
+    // Column groups add a wider gap between groups, and captioned ones a
+    // caption above each row; row groups add a wider gap between blocks
+    let group_gaps = grid
+        .columns
+        .saturating_sub(1)
+        .checked_div(grid.column_group)
+        .unwrap_or(0);
+    let label_cells = match grid.column_group {
+        group if group > 0 && grid.column_captions => GROUP_LABEL_CELLS,
+        _ => 0.0,
+    };
+    let row_group_gaps = grid
+        .rows
+        .saturating_sub(1)
+        .checked_div(grid.row_group)
+        .unwrap_or(0);
+    let columns = grid.columns as f32 + group_gaps as f32 * GROUP_GAP_CELLS;
+    let row_pitch = 1.0 + label_cells;
+    let rows = grid.rows as f32 * row_pitch + row_group_gaps as f32 * GROUP_GAP_CELLS;
 
     // Calculate cell size based on grid dimensions
-    let cell_width = grid_width as f32 / grid.columns as f32;
-    let cell_height = grid_height as f32 / grid.rows as f32;
+    let cell_width = grid_width as f32 / columns;
+    let cell_height = grid_height as f32 / rows;
     let cell_size = cell_width.min(cell_height);
 
     let gap = (cell_size * GAP_PERCENT) as u32;
     let actual_cell_size = (cell_size - gap as f32) as u32;
     let corner_radius = (actual_cell_size as f32 * CORNER_RADIUS_PERCENT) as u32;
 
-    // Center the grid
-    let total_grid_width = (cell_size * grid.columns as f32) as u32;
-    let total_grid_height = (cell_size * grid.rows as f32) as u32;
-    let start_x = (width - total_grid_width) / 2;
-    let start_y = header_height + (grid_height - total_grid_height) / 2 + padding_y;
+    // Center the grid together with its labels
+    let total_grid_width = (cell_size * columns) as u32;
+    let total_grid_height = (cell_size * rows) as u32;
+    let start_x = (width + row_label_width).saturating_sub(caption_width + total_grid_width) / 2;
+    let start_y = header_height
+        + column_label_height
+        + grid_height.saturating_sub(total_grid_height) / 2
+        + padding_y;
+
+    // Top-left corner of the cell in `col` and `row`
+    let cell_x = |col: usize| {
+        let group_offset = match grid.column_group {
+            0 => 0.0,
+            group => (col / group) as f32 * GROUP_GAP_CELLS,
+        };
+        start_x + ((col as f32 + group_offset) * cell_size) as u32 + gap / 2
+    };
+    let cell_y = |row: usize| {
+        let group_offset = match grid.row_group {
+            0 => 0.0,
+            group => (row / group) as f32 * GROUP_GAP_CELLS,
+        };
+        start_y
+            + ((row as f32 * row_pitch + label_cells + group_offset) * cell_size) as u32
+            + gap / 2
+    };
 
     // Draw each week cell
     for (i, week) in grid.weeks.iter().enumerate() {
-        let col = i % grid.columns;
-        let row = i / grid.columns;
+        let (col, row) = grid.cell_position(i);
+        let (x, y) = (cell_x(col), cell_y(row));
 
-        let x = start_x + (col as f32 * cell_size) as u32 + gap / 2;
-        let y = start_y + (row as f32 * cell_size) as u32 + gap / 2;
+        // Caption each column group with the label of its first cell
+        if let (Some(label), true) = (&week.label, label_cells > 0.0) {
+            if col % grid.column_group == 0 {
+                let group_width = (grid.column_group as f32 * cell_size) as u32 - gap;
+                draw_text_centered(
+                    &mut img,
+                    label,
+                    x + group_width / 2,
+                    y - (label_cells * cell_size / 2.0) as u32,
+                    (label_cells * cell_size * 0.6) as u32,
+                    colors.text,
+                );
+            }
+        }
 
-        let cell_color = match week.status {
-            WeekStatus::Past => colors.past_week,
-            WeekStatus::Current => colors.current_week,
-            WeekStatus::Future => colors.future_week,
+        // Events win over journal ratings, ratings over heatmap values and
+        // values over chapters; theme colors are the fallback
+        let past = week.status == WeekStatus::Past;
+        let marked = week
+            .rating
+            .map(rating_color)
+            .or(week.value.map(value_color))
+            .filter(|_| past);
+        let cell_color = match (week.event, marked, week.chapter, week.status) {
+            (Some(event), _, _, _) => event_colors[event],
+            (None, _, _, WeekStatus::Current) => colors.current_week,
+            (None, Some(color), _, _) => color,
+            (None, None, Some(chapter), WeekStatus::Past) => chapter_colors[chapter],
+            (None, None, Some(chapter), WeekStatus::Future) => blend(
+                colors.background,
+                chapter_colors[chapter],
+                FUTURE_CHAPTER_OPACITY,
+            ),
+            (None, None, None, WeekStatus::Past) => colors.past_week,
+            (None, None, None, WeekStatus::Future) => colors.future_week,
         };
 
+        // In family mode, split the cell into one stripe per person alive that week
+        let mut cell_colors = vec![cell_color];
+        if week.event.is_none()
+            && marked.is_none()
+            && week.status != WeekStatus::Current
+            && !week.people.is_empty()
+        {
+            cell_colors = week
+                .people
+                .iter()
+                .map(|&person| match week.status {
+                    WeekStatus::Future => blend(
+                        colors.background,
+                        person_colors[person],
+                        FUTURE_CHAPTER_OPACITY,
+                    ),
+                    _ => person_colors[person],
+                })
+                .collect();
+        }
+
+        // Fade future weeks by the probability of living to see them
+        if let (None, Some(survival)) = (week.event, week.survival) {
+            for color in &mut cell_colors {
+                *color = blend(colors.background, *color, survival as f32);
+            }
+        }
+
+        // Break weeks are drawn hollow, exam weeks half filled and free weeks
+        // tinted; events and the current week keep their usual look
+        let category = match (week.event, week.status) {
+            (None, WeekStatus::Past | WeekStatus::Future) => week.category,
+            _ => WeekCategory::Regular,
+        };
+        if category == WeekCategory::Exam {
+            cell_colors = vec![cell_colors[0], colors.background];
+        }
+
+        // Free weeks (vacation, holidays) get a second tone next to work weeks
+        if category == WeekCategory::Free {
+            for color in &mut cell_colors {
+                *color = blend(*color, colors.accent, FREE_WEEK_TINT);
+            }
+        }
+
         // Draw the cell (rounded rectangle)
-        draw_rounded_rect(
-            &mut img,
-            x,
-            y,
-            actual_cell_size,
-            actual_cell_size,
-            corner_radius,
-            cell_color,
-        );
+        if category == WeekCategory::Break {
+            draw_rounded_rect_outline(
+                &mut img,
+                x,
+                y,
+                actual_cell_size,
+                actual_cell_size,
+                corner_radius,
+                cell_colors[0],
+                (actual_cell_size / 10).max(1),
+            );
+        } else if let (WeekStatus::Current, None, false) = (
+            week.status,
+            week.event,
+            options.current_fill == CurrentFill::Solid,
+        ) {
+            // Fill the current cell in proportion to the days elapsed,
+            // counting today: Thursday in a Monday week is 4/7 filled
+            let elapsed = (today.min(week.end_date) - week.start_date).num_days() + 1;
+            let length = (week.end_date - week.start_date).num_days() + 1;
+            let fraction = elapsed.max(0) as f32 / length as f32;
+            draw_rounded_rect(
+                &mut img,
+                x,
+                y,
+                actual_cell_size,
+                actual_cell_size,
+                corner_radius,
+                colors.future_week,
+            );
+            draw_partial_rounded_rect(
+                &mut img,
+                x,
+                y,
+                actual_cell_size,
+                actual_cell_size,
+                corner_radius,
+                cell_colors[0],
+                fraction,
+                options.current_fill,
+            );
+        } else {
+            draw_striped_rounded_rect(
+                &mut img,
+                x,
+                y,
+                actual_cell_size,
+                actual_cell_size,
+                corner_radius,
+                &cell_colors,
+            );
+        }
+        if category == WeekCategory::Exam {
+            draw_rounded_rect_outline(
+                &mut img,
+                x,
+                y,
+                actual_cell_size,
+                actual_cell_size,
+                corner_radius,
+                cell_colors[0],
+                (actual_cell_size / 10).max(1),
+            );
+        }
 
         // Draw accent border for current week
         if week.status == WeekStatus::Current {
@@ -86,6 +499,41 @@ pub fn render_grid(grid: &WeekGrid, theme: &Theme, width: u32, height: u32) -> D
         }
     }
 
+    // Row labels sit right-aligned against the grid, block captions to its
+    // right and column numbers above it
+    for (row, label) in &row_labels {
+        let label_width = char_width * label.chars().count() as u32;
+        draw_text(
+            &mut img,
+            label,
+            start_x.saturating_sub(label_width + label_size / 2),
+            (cell_y(*row) + actual_cell_size / 2).saturating_sub(label_size / 2),
+            label_size,
+            colors.text,
+        );
+    }
+    for (first, last, caption) in &row_captions {
+        draw_text(
+            &mut img,
+            caption,
+            start_x + total_grid_width + label_size / 2,
+            ((cell_y(*first) + cell_y(*last) + actual_cell_size) / 2)
+                .saturating_sub(label_size / 2),
+            label_size,
+            colors.text,
+        );
+    }
+    for &col in &column_labels {
+        draw_text_centered(
+            &mut img,
+            &(col + 1).to_string(),
+            cell_x(col) + actual_cell_size / 2,
+            start_y - column_label_height / 2,
+            label_size,
+            colors.text,
+        );
+    }
+
     // Draw title (simple pixel-based text rendering)
     draw_text_centered(
         &mut img,
@@ -97,32 +545,117 @@ pub fn render_grid(grid: &WeekGrid, theme: &Theme, width: u32, height: u32) -> D
     );
 
     // Draw subtitle
-    draw_text_centered(
-        &mut img,
-        &grid.subtitle,
-        width / 2,
-        padding_y + title_height + subtitle_height / 2,
-        subtitle_height / 2,
-        colors.text,
-    );
+    for (i, line) in subtitle_lines.iter().enumerate() {
+        draw_text_centered(
+            &mut img,
+            line,
+            width / 2,
+            padding_y + title_height + subtitle_height * i as u32 + subtitle_height / 2,
+            subtitle_height / 2,
+            colors.text,
+        );
+    }
+
+    // Draw legend
+    if !legend.is_empty() {
+        draw_legend(
+            &mut img,
+            &legend,
+            width / 2,
+            height - padding_y - legend_height / 2,
+            legend_height / 2,
+            colors.text,
+        );
+    }
 
     DynamicImage::ImageRgba8(img)
 }
 
+/// Resolve the display color of a legend entry
+fn legend_color(entry: &LegendEntry, index: usize) -> [u8; 4] {
+    entry
+        .color
+        .as_deref()
+        .map(parse_hex_color)
+        .unwrap_or(EVENT_PALETTE[index % EVENT_PALETTE.len()])
+}
+
+/// Color for a journal rating, clamped to 1-5
+fn rating_color(rating: u8) -> [u8; 4] {
+    RATING_COLORS[rating.clamp(1, 5) as usize - 1]
+}
+
+/// Format a heatmap value for the legend, without decimals once it's large
+fn format_value(value: f64) -> String {
+    if value.abs() >= 10.0 || value.fract() == 0.0 {
+        format!("{:.0}", value)
+    } else {
+        format!("{:.1}", value)
+    }
+}
+
+/// Linearly interpolate between two colors (`t = 0` is `from`, `t = 1` is `to`)
+fn blend(from: [u8; 4], to: [u8; 4], t: f32) -> [u8; 4] {
+    let t = t.clamp(0.0, 1.0);
+    let mut out = [0; 4];
+    for i in 0..4 {
+        out[i] = (from[i] as f32 + (to[i] as f32 - from[i] as f32) * t).round() as u8;
+    }
+    out
+}
+
+/// Draw a centered, single-line legend of colored swatches and labels
+fn draw_legend(
+    img: &mut RgbaImage,
+    entries: &[(&str, [u8; 4])],
+    center_x: u32,
+    center_y: u32,
+    font_size: u32,
+    text_color: [u8; 4],
+) {
+    let char_width = (font_size as f32 * 0.6) as u32;
+    let swatch = font_size;
+    let spacing = font_size * 2;
+
+    let entry_width = |label: &str| swatch + char_width + char_width * label.chars().count() as u32;
+    let total_width: u32 = entries
+        .iter()
+        .map(|(label, _)| entry_width(label))
+        .sum::<u32>()
+        + spacing * entries.len().saturating_sub(1) as u32;
+
+    let mut x = center_x.saturating_sub(total_width / 2);
+    let y = center_y.saturating_sub(font_size / 2);
+
+    for &(label, color) in entries {
+        draw_rounded_rect(img, x, y, swatch, swatch, swatch / 4, color);
+        draw_text(
+            img,
+            label,
+            x + swatch + char_width,
+            y,
+            font_size,
+            text_color,
+        );
+        x += entry_width(label) + spacing;
+    }
+}
+
 /// Save a rendered grid to a file
 pub fn save_grid(image: &DynamicImage, path: &Path) -> Result<(), image::ImageError> {
     image.save(path)
 }
 
-/// Render and save in one step
+/// Render with the given options and save in one step
 pub fn render_and_save(
     grid: &WeekGrid,
     theme: &Theme,
     width: u32,
     height: u32,
+    options: &RenderOptions,
     path: &Path,
 ) -> Result<(), image::ImageError> {
-    let image = render_grid(grid, theme, width, height);
+    let image = render_grid_with(grid, theme, width, height, options);
     save_grid(&image, path)
 }
 
@@ -135,9 +668,21 @@ fn draw_rounded_rect(
     height: u32,
     radius: u32,
     color: [u8; 4],
+) {
+    draw_striped_rounded_rect(img, x, y, width, height, radius, &[color]);
+}
+
+/// Draw a filled rounded rectangle split into equal vertical stripes, one per color
+fn draw_striped_rounded_rect(
+    img: &mut RgbaImage,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    radius: u32,
+    colors: &[[u8; 4]],
 ) {
     let radius = radius.min(width / 2).min(height / 2);
-    let pixel = Rgba(color);
     let (img_width, img_height) = img.dimensions();
 
     for dy in 0..height {
@@ -149,25 +694,9 @@ fn draw_rounded_rect(
                 continue;
             }
 
-            // Check if pixel is inside rounded rectangle
-            let inside = if dx < radius && dy < radius {
-                // Top-left corner
-                is_in_circle(dx, dy, radius, radius, radius)
-            } else if dx >= width - radius && dy < radius {
-                // Top-right corner
-                is_in_circle(dx, dy, width - radius - 1, radius, radius)
-            } else if dx < radius && dy >= height - radius {
-                // Bottom-left corner
-                is_in_circle(dx, dy, radius, height - radius - 1, radius)
-            } else if dx >= width - radius && dy >= height - radius {
-                // Bottom-right corner
-                is_in_circle(dx, dy, width - radius - 1, height - radius - 1, radius)
-            } else {
-                true
-            };
-
-            if inside {
-                img.put_pixel(px, py, pixel);
+            if in_rounded_rect(dx, dy, width, height, radius) {
+                let stripe = (dx as usize * colors.len() / width as usize).min(colors.len() - 1);
+                img.put_pixel(px, py, Rgba(colors[stripe]));
             }
         }
     }
@@ -208,26 +737,82 @@ fn draw_rounded_rect_outline(
                 continue;
             }
 
-            // Check if pixel is inside rounded rectangle
-            let inside = if dx < radius && dy < radius {
-                is_in_circle(dx, dy, radius, radius, radius)
-            } else if dx >= width - radius && dy < radius {
-                is_in_circle(dx, dy, width - radius - 1, radius, radius)
-            } else if dx < radius && dy >= height - radius {
-                is_in_circle(dx, dy, radius, height - radius - 1, radius)
-            } else if dx >= width - radius && dy >= height - radius {
-                is_in_circle(dx, dy, width - radius - 1, height - radius - 1, radius)
-            } else {
-                true
+            if in_rounded_rect(dx, dy, width, height, radius) {
+                img.put_pixel(px, py, pixel);
+            }
+        }
+    }
+}
+
+/// Draw the leading `fraction` (0-1) of a filled rounded rectangle
+///
+/// Horizontal fills from the left edge; radial sweeps clockwise from the
+/// top like a pie chart. Solid ignores the fraction.
+#[allow(clippy::too_many_arguments)]
+fn draw_partial_rounded_rect(
+    img: &mut RgbaImage,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    radius: u32,
+    color: [u8; 4],
+    fraction: f32,
+    fill: CurrentFill,
+) {
+    let radius = radius.min(width / 2).min(height / 2);
+    let pixel = Rgba(color);
+    let (img_width, img_height) = img.dimensions();
+    let fraction = fraction.clamp(0.0, 1.0);
+
+    for dy in 0..height {
+        for dx in 0..width {
+            let px = x + dx;
+            let py = y + dy;
+
+            if px >= img_width || py >= img_height {
+                continue;
+            }
+
+            // Measure from pixel centers so a full fraction covers every pixel
+            let cx = dx as f32 + 0.5 - width as f32 / 2.0;
+            let cy = dy as f32 + 0.5 - height as f32 / 2.0;
+            let filled = match fill {
+                CurrentFill::Solid => true,
+                CurrentFill::Horizontal => dx as f32 + 0.5 <= fraction * width as f32,
+                CurrentFill::Radial => {
+                    // Angle clockwise from twelve o'clock, in 0..TAU
+                    let angle = cx.atan2(-cy).rem_euclid(std::f32::consts::TAU);
+                    angle <= fraction * std::f32::consts::TAU
+                }
             };
 
-            if inside {
+            if filled && in_rounded_rect(dx, dy, width, height, radius) {
                 img.put_pixel(px, py, pixel);
             }
         }
     }
 }
 
+/// Check if a pixel offset lies inside a rounded rectangle anchored at the origin
+fn in_rounded_rect(dx: u32, dy: u32, width: u32, height: u32, radius: u32) -> bool {
+    if dx < radius && dy < radius {
+        // Top-left corner
+        is_in_circle(dx, dy, radius, radius, radius)
+    } else if dx >= width - radius && dy < radius {
+        // Top-right corner
+        is_in_circle(dx, dy, width - radius - 1, radius, radius)
+    } else if dx < radius && dy >= height - radius {
+        // Bottom-left corner
+        is_in_circle(dx, dy, radius, height - radius - 1, radius)
+    } else if dx >= width - radius && dy >= height - radius {
+        // Bottom-right corner
+        is_in_circle(dx, dy, width - radius - 1, height - radius - 1, radius)
+    } else {
+        true
+    }
+}
+
 /// Check if a point is inside a circle
 fn is_in_circle(x: u32, y: u32, cx: u32, cy: u32, r: u32) -> bool {
     let dx = x as i32 - cx as i32;
@@ -252,11 +837,17 @@ fn draw_text_centered(
     let start_x = center_x.saturating_sub(total_width / 2);
     let start_y = center_y.saturating_sub(font_size / 2);
 
+    draw_text(img, text, start_x, start_y, font_size, color);
+}
+
+/// Draw left-aligned text with its top-left corner at (x, y)
+fn draw_text(img: &mut RgbaImage, text: &str, x: u32, y: u32, font_size: u32, color: [u8; 4]) {
+    let char_width = (font_size as f32 * 0.6) as u32;
     let pixel = Rgba(color);
 
     for (i, c) in text.chars().enumerate() {
-        let char_x = start_x + (i as u32 * char_width);
-        draw_char(img, c, char_x, start_y, font_size, pixel);
+        let char_x = x + (i as u32 * char_width);
+        draw_char(img, c, char_x, y, font_size, pixel);
     }
 }
 
@@ -413,6 +1004,21 @@ fn get_char_bitmap(c: char) -> [u8; 8] {
         '%' => [
             0b110001, 0b110011, 0b000110, 0b001100, 0b011000, 0b110011, 0b100011, 0b000000,
         ],
+        '.' => [
+            0b000000, 0b000000, 0b000000, 0b000000, 0b000000, 0b001100, 0b001100, 0b000000,
+        ],
+        ',' => [
+            0b000000, 0b000000, 0b000000, 0b000000, 0b001100, 0b001100, 0b011000, 0b000000,
+        ],
+        ':' => [
+            0b000000, 0b001100, 0b001100, 0b000000, 0b001100, 0b001100, 0b000000, 0b000000,
+        ],
+        '/' => [
+            0b000001, 0b000011, 0b000110, 0b001100, 0b011000, 0b110000, 0b100000, 0b000000,
+        ],
+        '\'' => [
+            0b001100, 0b001100, 0b011000, 0b000000, 0b000000, 0b000000, 0b000000, 0b000000,
+        ],
         _ => [
             0b000000, 0b000000, 0b000000, 0b000000, 0b000000, 0b000000, 0b000000, 0b000000,
         ],
@@ -422,12 +1028,20 @@ fn get_char_bitmap(c: char) -> [u8; 8] {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::{Grouping, LifeEvent, Person};
+    use crate::data::DataSeries;
+    use crate::journal::Journal;
     use crate::modes::Mode;
     use chrono::NaiveDate;
 
+    /// Fixed reference date so tests don't depend on the current date
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, 13).unwrap()
+    }
+
     #[test]
     fn test_render_year_end() {
-        let grid = WeekGrid::calculate(&Mode::YearEnd);
+        let grid = WeekGrid::calculate_at(&Mode::YearEnd, today());
         let image = render_grid(&grid, &Theme::SoftDark, 1920, 1080);
 
         assert_eq!(image.width(), 1920);
@@ -437,13 +1051,399 @@ mod tests {
     #[test]
     fn test_render_life_mode() {
         let dob = NaiveDate::from_ymd_opt(1990, 1, 1).unwrap();
-        let grid = WeekGrid::calculate(&Mode::Life {
-            dob,
-            lifespan_years: 80,
-        });
+        let grid = WeekGrid::calculate_at(
+            &Mode::Life {
+                dob,
+                lifespan_years: 80,
+                age_rows: false,
+            },
+            today(),
+        );
         let image = render_grid(&grid, &Theme::TerminalGreen, 1920, 1080);
 
         assert_eq!(image.width(), 1920);
         assert_eq!(image.height(), 1080);
     }
+
+    #[test]
+    fn test_render_labels() {
+        let grid = WeekGrid::calculate_at(
+            &Mode::Life {
+                dob: NaiveDate::from_ymd_opt(1990, 5, 17).unwrap(),
+                lifespan_years: 80,
+                age_rows: true,
+            },
+            today(),
+        );
+        let colors = Theme::SoftDark.colors();
+        let text_pixels = |options: &RenderOptions| {
+            let image = render_grid_with(&grid, &Theme::SoftDark, 1920, 1080, options).to_rgba8();
+            // Leftmost column holding a past cell
+            let left = (0..image.width())
+                .find(|&x| (0..image.height()).any(|y| image.get_pixel(x, y).0 == colors.past_week))
+                .unwrap();
+            let text = image.pixels().filter(|p| p.0 == colors.text).count();
+            (left, text)
+        };
+
+        let (plain_left, plain_text) = text_pixels(&RenderOptions::default());
+        let (left, text) = text_pixels(&RenderOptions {
+            row_labels: true,
+            column_labels: true,
+            ..Default::default()
+        });
+        // The grid makes room for the row labels and more text is drawn
+        assert!(left > plain_left);
+        assert!(text > plain_text);
+
+        let (_, columns_only) = text_pixels(&RenderOptions {
+            column_labels: true,
+            ..Default::default()
+        });
+        assert!(plain_text < columns_only && columns_only < text);
+    }
+
+    #[test]
+    fn test_render_empty_grid() {
+        let mut grid = WeekGrid::calculate_at(&Mode::Term { terms: Vec::new() }, today());
+        grid.apply_grouping(&Grouping {
+            rows: 4,
+            columns: 4,
+            captions: true,
+        });
+        let image = render_grid(&grid, &Theme::SoftDark, 320, 200);
+        assert_eq!((image.width(), image.height()), (320, 200));
+    }
+
+    #[test]
+    fn test_render_labels_on_narrow_screen() {
+        let mut grid = WeekGrid::calculate_at(
+            &Mode::Life {
+                dob: NaiveDate::from_ymd_opt(1990, 5, 17).unwrap(),
+                lifespan_years: 80,
+                age_rows: true,
+            },
+            today(),
+        );
+        grid.apply_grouping(&Grouping {
+            rows: 10,
+            columns: 0,
+            captions: true,
+        });
+        let options = RenderOptions {
+            row_labels: true,
+            column_labels: true,
+            ..Default::default()
+        };
+        // Labels and captions wider than the screen leave no room for the grid
+        for (width, height) in [(60, 1080), (16, 16)] {
+            let image = render_grid_with(&grid, &Theme::SoftDark, width, height, &options);
+            assert_eq!((image.width(), image.height()), (width, height));
+        }
+    }
+
+    #[test]
+    fn test_render_current_week_fill() {
+        let grid = WeekGrid::calculate_at(
+            &Mode::Life {
+                dob: NaiveDate::from_ymd_opt(1990, 5, 17).unwrap(),
+                lifespan_years: 80,
+                age_rows: false,
+            },
+            today(),
+        );
+        let colors = Theme::SoftDark.colors();
+        // Current week pixels, including the accent outline drawn in the same color
+        let filled = |current_fill: CurrentFill, day: u32| {
+            let options = RenderOptions {
+                current_fill,
+                as_of: NaiveDate::from_ymd_opt(2024, 3, day),
+                ..Default::default()
+            };
+            let image = render_grid_with(&grid, &Theme::SoftDark, 1920, 1080, &options);
+            image
+                .to_rgba8()
+                .pixels()
+                .filter(|p| p.0 == colors.current_week)
+                .count()
+        };
+
+        // The grid's current week runs Monday 11 to Sunday 17 March
+        let solid = filled(CurrentFill::Solid, 13);
+        for fill in [CurrentFill::Horizontal, CurrentFill::Radial] {
+            let (monday, thursday, sunday) = (filled(fill, 11), filled(fill, 14), filled(fill, 17));
+            assert!(monday < thursday && thursday < sunday);
+            assert_eq!(sunday, solid);
+            // 6/7 of the cell is empty on Monday, 3/7 on Thursday
+            let ratio = (solid - thursday) as f32 / (solid - monday) as f32;
+            assert!((ratio - 0.5).abs() < 0.1, "{:?}: {}", fill, ratio);
+        }
+    }
+
+    #[test]
+    fn test_render_with_events() {
+        let dob = NaiveDate::from_ymd_opt(1990, 1, 1).unwrap();
+        let mut grid = WeekGrid::calculate_at(
+            &Mode::Life {
+                dob,
+                lifespan_years: 80,
+                age_rows: false,
+            },
+            today(),
+        );
+        grid.apply_events(&[LifeEvent {
+            name: "Wedding".to_string(),
+            date: NaiveDate::from_ymd_opt(2015, 6, 13).unwrap(),
+            color: Some("#00FF00".to_string()),
+        }]);
+        let image = render_grid(&grid, &Theme::MinimalInk, 1920, 1080).to_rgba8();
+
+        assert!(image.pixels().any(|p| p.0 == [0, 255, 0, 255]));
+    }
+
+    #[test]
+    fn test_render_full_year_month_rows() {
+        let grid = WeekGrid::calculate_at(&Mode::FullYear { month_rows: true }, today());
+        let image = render_grid(&grid, &Theme::SoftDark, 1920, 1080).to_rgba8();
+
+        // Elapsed weeks are drawn in the past color
+        let past = Theme::SoftDark.colors().past_week;
+        assert!(image.pixels().any(|p| p.0 == past));
+
+        // Before the year starts nothing has elapsed yet
+        let new_year = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let grid = WeekGrid::calculate_at(&Mode::FullYear { month_rows: true }, new_year);
+        let image = render_grid(&grid, &Theme::SoftDark, 1920, 1080).to_rgba8();
+        assert!(!image.pixels().any(|p| p.0 == past));
+    }
+
+    #[test]
+    fn test_render_family_mode() {
+        let person = |name: &str, year: i32, color: &str| Person {
+            name: name.to_string(),
+            dob: NaiveDate::from_ymd_opt(year, 1, 1).unwrap(),
+            lifespan_years: Some(80),
+            color: Some(color.to_string()),
+        };
+        let grid = WeekGrid::calculate_at(
+            &Mode::Family {
+                people: vec![
+                    person("Sam", 1990, "#FF0000"),
+                    person("Alex", 1992, "#0000FF"),
+                ],
+            },
+            today(),
+        );
+        let image = render_grid(&grid, &Theme::SoftDark, 1920, 1080).to_rgba8();
+
+        // Each person's color shows up in the cells and the legend
+        assert!(image.pixels().any(|p| p.0 == [255, 0, 0, 255]));
+        assert!(image.pixels().any(|p| p.0 == [0, 0, 255, 255]));
+    }
+
+    #[test]
+    fn test_render_sprint_groups() {
+        let grid = WeekGrid::calculate_at(
+            &Mode::Sprint {
+                anchor: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                release: NaiveDate::from_ymd_opt(2024, 6, 28).unwrap(),
+                sprint_weeks: 2,
+            },
+            today(),
+        );
+        let image = render_grid(&grid, &Theme::SoftDark, 1920, 1080).to_rgba8();
+        let past = Theme::SoftDark.colors().past_week;
+
+        // Take the pixel row crossing the most past cells (the first grid row)
+        let y = (0..image.height())
+            .max_by_key(|&y| {
+                (0..image.width())
+                    .filter(|&x| image.get_pixel(x, y).0 == past)
+                    .count()
+            })
+            .unwrap();
+        let mut cell_starts = Vec::new();
+        let mut cell_ends = Vec::new();
+        let mut inside = false;
+        for x in 0..image.width() {
+            let is_past = image.get_pixel(x, y).0 == past;
+            if is_past && !inside {
+                cell_starts.push(x);
+            } else if !is_past && inside {
+                cell_ends.push(x);
+            }
+            inside = is_past;
+        }
+        let gaps: Vec<u32> = cell_starts[1..]
+            .iter()
+            .zip(&cell_ends)
+            .map(|(start, end)| start - end)
+            .collect();
+
+        // Six cells in three sprints: gaps alternate narrow (within) and wide (between)
+        assert_eq!(gaps.len(), 5);
+        assert!(gaps[1] > gaps[0] * 2);
+        assert!(gaps[3] > gaps[2] * 2);
+    }
+
+    #[test]
+    fn test_render_row_groups() {
+        let mut grid = WeekGrid::calculate_at(
+            &Mode::Life {
+                dob: NaiveDate::from_ymd_opt(1990, 1, 1).unwrap(),
+                lifespan_years: 80,
+                age_rows: false,
+            },
+            today(),
+        );
+        grid.apply_grouping(&Grouping {
+            rows: 10,
+            columns: 13,
+            captions: false,
+        });
+        let colors = Theme::SoftDark.colors();
+        let image = render_grid(&grid, &Theme::SoftDark, 1920, 1080).to_rgba8();
+
+        // Take the pixel column crossing the most past cells (the first grid column)
+        let x = (0..image.width())
+            .max_by_key(|&x| {
+                (0..image.height())
+                    .filter(|&y| image.get_pixel(x, y).0 == colors.past_week)
+                    .count()
+            })
+            .unwrap();
+        let mut gaps = Vec::new();
+        let mut last_past = None;
+        for y in 0..image.height() {
+            if image.get_pixel(x, y).0 == colors.past_week {
+                if let Some(last) = last_past.filter(|&last| y > last + 1) {
+                    gaps.push(y - last);
+                }
+                last_past = Some(y);
+            }
+        }
+
+        // 35 rows have begun; the gaps closing each decade are wider
+        assert_eq!(gaps.len(), 34);
+        for (i, &gap) in gaps.iter().enumerate() {
+            if i % 10 == 9 {
+                assert!(gap > gaps[0] * 2);
+            } else {
+                assert!(gap <= gaps[0] + 1);
+            }
+        }
+
+        // Captions are drawn beside each decade
+        let text = |grid: &WeekGrid| {
+            let image = render_grid(grid, &Theme::SoftDark, 1920, 1080).to_rgba8();
+            image.pixels().filter(|p| p.0 == colors.text).count()
+        };
+        let plain = text(&grid);
+        grid.row_captions = true;
+        assert!(text(&grid) > plain);
+    }
+
+    #[test]
+    fn test_render_break_weeks() {
+        let mut grid = WeekGrid::calculate_at(
+            &Mode::Range {
+                start: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                end: NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+                title: None,
+            },
+            today(),
+        );
+        let past = Theme::SoftDark.colors().past_week;
+        let count_past = |grid: &WeekGrid| {
+            let image = render_grid(grid, &Theme::SoftDark, 1920, 1080).to_rgba8();
+            image.pixels().filter(|p| p.0 == past).count()
+        };
+        let solid = count_past(&grid);
+
+        // A hollow break cell paints less than a solid one, an exam cell in between
+        grid.weeks[0].category = WeekCategory::Exam;
+        let exam = count_past(&grid);
+        grid.weeks[0].category = WeekCategory::Break;
+        let hollow = count_past(&grid);
+        assert!(hollow < exam && exam < solid);
+    }
+
+    #[test]
+    fn test_render_free_weeks() {
+        let mut grid = WeekGrid::calculate_at(
+            &Mode::Retirement {
+                dob: None,
+                retire_on: NaiveDate::from_ymd_opt(2025, 3, 1).unwrap(),
+                vacation_weeks: 0,
+                holiday_days: 0,
+            },
+            today(),
+        );
+        let colors = Theme::SoftDark.colors();
+        let tinted = blend(colors.future_week, colors.accent, FREE_WEEK_TINT);
+        let has_tint = |grid: &WeekGrid| {
+            let image = render_grid(grid, &Theme::SoftDark, 1920, 1080).to_rgba8();
+            image.pixels().any(|p| p.0 == tinted)
+        };
+
+        assert!(!has_tint(&grid));
+        grid.weeks[10].category = WeekCategory::Free;
+        assert!(has_tint(&grid));
+    }
+
+    #[test]
+    fn test_render_journal_ratings() {
+        let date = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+        let has_rating = |journal: &Journal| {
+            let mut grid = WeekGrid::calculate_at(&Mode::FullYear { month_rows: false }, today());
+            grid.apply_journal(journal);
+            let image = render_grid(&grid, &Theme::SoftDark, 1920, 1080).to_rgba8();
+            image.pixels().any(|p| p.0 == rating_color(5))
+        };
+
+        // The current week keeps its highlight; rated past weeks take the rating color
+        let mut journal = Journal::default();
+        journal.add(date(3, 11), "This week", Some(5)).unwrap();
+        assert!(!has_rating(&journal));
+        journal.add(date(2, 5), "Great week", Some(5)).unwrap();
+        assert!(has_rating(&journal));
+    }
+
+    #[test]
+    fn test_render_heatmap() {
+        let date = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+        let data = DataSeries::new(
+            "km".to_string(),
+            vec![(date(1, 8), 5.0), (date(2, 5), 20.0), (date(2, 6), 22.5)],
+        );
+        let colors = Theme::SoftDark.colors();
+
+        let mut grid = WeekGrid::calculate_at(&Mode::FullYear { month_rows: false }, today());
+        let plain = render_grid(&grid, &Theme::SoftDark, 1920, 1080).to_rgba8();
+        assert!(!plain.pixels().any(|p| p.0 == colors.scale_high));
+
+        // The busiest week takes the top of the scale, the quietest the bottom
+        grid.apply_data(&data);
+        let image = render_grid(&grid, &Theme::SoftDark, 1920, 1080).to_rgba8();
+        assert!(image.pixels().any(|p| p.0 == colors.scale_high));
+        assert!(image.pixels().any(|p| p.0 == colors.scale_low));
+        assert_eq!(format_value(42.5), "42");
+        assert_eq!(format_value(2.25), "2.2");
+    }
+
+    #[test]
+    fn test_blend() {
+        assert_eq!(
+            blend([0, 0, 0, 255], [200, 100, 50, 255], 0.0),
+            [0, 0, 0, 255]
+        );
+        assert_eq!(
+            blend([0, 0, 0, 255], [200, 100, 50, 255], 1.0),
+            [200, 100, 50, 255]
+        );
+        assert_eq!(
+            blend([0, 0, 0, 255], [200, 100, 50, 255], 0.5),
+            [100, 50, 25, 255]
+        );
+    }
 }
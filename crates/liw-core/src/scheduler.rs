@@ -1,4 +1,4 @@
-//! OS scheduler integration for weekly wallpaper regeneration
+//! OS scheduler integration for daily wallpaper regeneration
 //!
 //! Creates scheduled tasks on Windows (Task Scheduler) and macOS (launchd).
 
@@ -7,7 +7,9 @@ use std::env;
 use std::fs;
 use std::path::PathBuf;
 
-/// Install a weekly schedule to regenerate the wallpaper
+/// Install a daily schedule to regenerate the wallpaper
+///
+/// Daily runs keep day cells and the partially filled current week up to date.
 pub fn install_schedule() -> Result<()> {
     #[cfg(target_os = "windows")]
     {
@@ -30,7 +32,7 @@ pub fn install_schedule() -> Result<()> {
     }
 }
 
-/// Uninstall the weekly schedule
+/// Uninstall the daily schedule
 pub fn uninstall_schedule() -> Result<()> {
     #[cfg(target_os = "windows")]
     {
@@ -97,14 +99,12 @@ fn install_schedule_windows() -> Result<()> {
         .to_str()
         .context("Executable path contains invalid UTF-8")?;
 
-    // Create a weekly task that runs every Monday at 6:00 AM
+    // Create a daily task that runs at 6:00 AM
     let output = Command::new("schtasks")
         .args([
             "/Create",
             "/SC",
-            "WEEKLY",
-            "/D",
-            "MON",
+            "DAILY",
             "/TN",
             TASK_NAME,
             "/TR",
@@ -117,8 +117,8 @@ fn install_schedule_windows() -> Result<()> {
         .context("Failed to execute schtasks")?;
 
     if output.status.success() {
-        println!("Weekly schedule installed successfully.");
-        println!("The wallpaper will update every Monday at 6:00 AM.");
+        println!("Daily schedule installed successfully.");
+        println!("The wallpaper will update every day at 6:00 AM.");
         Ok(())
     } else {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -136,7 +136,7 @@ fn uninstall_schedule_windows() -> Result<()> {
         .context("Failed to execute schtasks")?;
 
     if output.status.success() {
-        println!("Weekly schedule removed successfully.");
+        println!("Daily schedule removed successfully.");
         Ok(())
     } else {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -193,7 +193,7 @@ fn install_schedule_macos() -> Result<()> {
     }
 
     // Create the plist file
-    // Schedule: Every Monday at 6:00 AM (Weekday 1 = Monday)
+    // Schedule: Every day at 6:00 AM
     let plist_content = format!(
         r#"<?xml version="1.0" encoding="UTF-8"?>
 <!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
@@ -208,8 +208,6 @@ fn install_schedule_macos() -> Result<()> {
     </array>
     <key>StartCalendarInterval</key>
     <dict>
-        <key>Weekday</key>
-        <integer>1</integer>
         <key>Hour</key>
         <integer>6</integer>
         <key>Minute</key>
@@ -232,8 +230,8 @@ fn install_schedule_macos() -> Result<()> {
         .context("Failed to execute launchctl")?;
 
     if output.status.success() {
-        println!("Weekly schedule installed successfully.");
-        println!("The wallpaper will update every Monday at 6:00 AM.");
+        println!("Daily schedule installed successfully.");
+        println!("The wallpaper will update every day at 6:00 AM.");
         Ok(())
     } else {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -256,7 +254,7 @@ fn uninstall_schedule_macos() -> Result<()> {
         // Remove the plist file
         fs::remove_file(&plist_path).context("Failed to remove plist file")?;
 
-        println!("Weekly schedule removed successfully.");
+        println!("Daily schedule removed successfully.");
     } else {
         println!("Schedule was not installed.");
     }
@@ -306,12 +304,12 @@ ExecStart={} generate
     let service_path = systemd_dir.join("liw-wallpaper.service");
     fs::write(&service_path, service_content).context("Failed to write service file")?;
 
-    // Create the timer file (every Monday at 6:00 AM)
+    // Create the timer file (every day at 6:00 AM)
     let timer_content = r#"[Unit]
-Description=Weekly Life in Weeks Wallpaper Update
+Description=Daily Life in Weeks Wallpaper Update
 
 [Timer]
-OnCalendar=Mon *-*-* 06:00:00
+OnCalendar=*-*-* 06:00:00
 Persistent=true
 
 [Install]
@@ -332,8 +330,8 @@ WantedBy=timers.target
         .context("Failed to enable timer")?;
 
     if output.status.success() {
-        println!("Weekly schedule installed successfully.");
-        println!("The wallpaper will update every Monday at 6:00 AM.");
+        println!("Daily schedule installed successfully.");
+        println!("The wallpaper will update every day at 6:00 AM.");
         Ok(())
     } else {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -360,7 +358,7 @@ fn uninstall_schedule_linux() -> Result<()> {
         .args(["--user", "daemon-reload"])
         .output();
 
-    println!("Weekly schedule removed successfully.");
+    println!("Daily schedule removed successfully.");
     Ok(())
 }
 
@@ -11,8 +11,10 @@ use base64::engine::general_purpose::STANDARD as BASE64;
 use base64::Engine;
 use chrono::NaiveDate;
 use liw_core::{
-    install_schedule, render_grid, renderer::save_grid, scheduler::is_schedule_installed,
-    set_wallpaper as core_set_wallpaper, uninstall_schedule, Config, Mode, Theme, WeekGrid,
+    install_schedule, render_grid_with, renderer::save_grid, scheduler::is_schedule_installed,
+    set_wallpaper as core_set_wallpaper, uninstall_schedule, Config, CountdownTarget, CurrentFill,
+    Granularity, GridOptions, Journal, Mode, ModeParams, Person, RenderOptions, Sex, SurvivalCurve,
+    Theme, WeekGrid, WeekStart,
 };
 use serde::{Deserialize, Serialize};
 use std::io::Cursor;
@@ -24,6 +26,29 @@ pub struct GenerateRequest {
     dob: Option<String>,
     lifespan: Option<u8>,
     months: Option<u8>,
+    start: Option<String>,
+    end: Option<String>,
+    title: Option<String>,
+    month_rows: Option<bool>,
+    age_rows: Option<bool>,
+    retirement_age: Option<u8>,
+    retirement_date: Option<String>,
+    vacation_weeks: Option<u8>,
+    public_holidays: Option<u8>,
+    fiscal_start_month: Option<u32>,
+    sprint_anchor: Option<String>,
+    release: Option<String>,
+    sprint_weeks: Option<u8>,
+    due: Option<String>,
+    born: Option<String>,
+    survival: Option<bool>,
+    labels: Option<bool>,
+    current_fill: Option<String>,
+    sex: Option<String>,
+    country: Option<String>,
+    granularity: Option<String>,
+    week_start: Option<String>,
+    as_of: Option<String>,
     theme: Option<String>,
     width: Option<u32>,
     height: Option<u32>,
@@ -41,6 +66,15 @@ pub struct GenerateResponse {
     rows: usize,
     /// Base64-encoded PNG image
     image_base64: String,
+    /// Calendar events that were skipped
+    warnings: Vec<String>,
+}
+
+/// Lifespan estimated from the embedded life tables
+#[derive(Debug, Serialize)]
+pub struct LifespanResponse {
+    lifespan_years: u8,
+    remaining_years: f64,
 }
 
 /// Current configuration state
@@ -53,6 +87,31 @@ pub struct ConfigState {
     screen_height: u32,
     default_mode: String,
     next_months: u8,
+    granularity: Granularity,
+    week_start: WeekStart,
+    range_start: Option<String>,
+    range_end: Option<String>,
+    range_title: Option<String>,
+    month_rows: bool,
+    age_rows: bool,
+    row_labels: bool,
+    column_labels: bool,
+    current_week_fill: CurrentFill,
+    retirement_age: u8,
+    retirement_date: Option<String>,
+    vacation_weeks: u8,
+    public_holidays: u8,
+    fiscal_start_month: u32,
+    sprint_anchor: Option<String>,
+    release_date: Option<String>,
+    sprint_weeks: u8,
+    due_date: Option<String>,
+    birth_date: Option<String>,
+    sex: Option<Sex>,
+    country: Option<String>,
+    survival_shading: bool,
+    targets: Vec<CountdownTarget>,
+    people: Vec<Person>,
     schedule_installed: bool,
 }
 
@@ -78,27 +137,107 @@ fn theme_name(theme: &Theme) -> String {
     }
 }
 
+/// Parse an optional YYYY-MM-DD date, falling back to a default
+fn parse_date_or(value: &Option<String>, default: Option<NaiveDate>) -> Option<NaiveDate> {
+    match value {
+        Some(s) => NaiveDate::parse_from_str(s, "%Y-%m-%d").ok(),
+        None => default,
+    }
+}
+
+/// Merge request overrides with the saved config
+fn mode_params(request: &GenerateRequest, config: &Config) -> ModeParams {
+    ModeParams {
+        dob: parse_date_or(&request.dob, config.dob),
+        lifespan: Some(request.lifespan.unwrap_or(config.lifespan_years)),
+        age_rows: request.age_rows.unwrap_or(config.age_rows),
+        months: Some(request.months.unwrap_or(config.next_months)),
+        start: parse_date_or(&request.start, config.range_start),
+        end: parse_date_or(&request.end, config.range_end),
+        title: request
+            .title
+            .clone()
+            .or_else(|| config.range_title.clone())
+            .filter(|t| !t.is_empty()),
+        targets: config.targets.clone(),
+        month_rows: request.month_rows.unwrap_or(config.month_rows),
+        people: config.people.clone(),
+        retirement_age: Some(request.retirement_age.unwrap_or(config.retirement_age)),
+        retirement_date: parse_date_or(&request.retirement_date, config.retirement_date),
+        vacation_weeks: Some(request.vacation_weeks.unwrap_or(config.vacation_weeks)),
+        holiday_days: Some(request.public_holidays.unwrap_or(config.public_holidays)),
+        terms: config.terms.clone(),
+        recurring: config.recurring.clone(),
+        fiscal_start_month: Some(
+            request
+                .fiscal_start_month
+                .unwrap_or(config.fiscal_start_month),
+        ),
+        sprint_anchor: parse_date_or(&request.sprint_anchor, config.sprint_anchor),
+        release: parse_date_or(&request.release, config.release_date),
+        sprint_weeks: Some(request.sprint_weeks.unwrap_or(config.sprint_weeks)),
+        due: parse_date_or(&request.due, config.due_date),
+        conceived: None,
+        born: parse_date_or(&request.born, config.birth_date),
+    }
+}
+
+/// Merge request calendar overrides with the saved config
+fn grid_options(request: &GenerateRequest, config: &Config) -> Result<GridOptions, String> {
+    let mut options = config.grid_options();
+    if let Some(ref g) = request.granularity {
+        options.granularity = Granularity::parse(g)?;
+    }
+    if let Some(ref w) = request.week_start {
+        options.week_start = WeekStart::parse(w)?;
+    }
+    options.as_of = parse_date_or(&request.as_of, None);
+    Ok(options)
+}
+
+/// Merge the request's label toggle and fill style with the saved render options
+fn render_options(request: &GenerateRequest, config: &Config) -> Result<RenderOptions, String> {
+    let mut options = config.render_options();
+    if let Some(enabled) = request.labels {
+        options.row_labels = enabled;
+        options.column_labels = enabled;
+    }
+    if let Some(ref fill) = request.current_fill {
+        options.current_fill = CurrentFill::parse(fill)?;
+    }
+    options.as_of = parse_date_or(&request.as_of, None);
+    Ok(options)
+}
+
+/// Survival curve for shading life mode, merging request overrides with the saved config
+fn survival_curve(
+    request: &GenerateRequest,
+    config: &Config,
+) -> Result<Option<SurvivalCurve>, String> {
+    let mut config = config.clone();
+    if let Some(enabled) = request.survival {
+        config.survival_shading = enabled;
+    }
+    if let Some(ref sex) = request.sex {
+        config.sex = Some(Sex::parse(sex)?);
+    }
+    if let Some(ref country) = request.country {
+        config.country = Some(country.clone());
+    }
+    config.survival_curve().map_err(|e| e.to_string())
+}
+
 /// Generate a preview image and return as base64
 #[tauri::command]
 fn generate_preview(request: GenerateRequest) -> Result<GenerateResponse, String> {
     // Load config for defaults
     let config = Config::load().unwrap_or_default();
 
-    // Parse DOB if provided
-    let dob = if let Some(ref dob_str) = request.dob {
-        NaiveDate::parse_from_str(dob_str, "%Y-%m-%d").ok()
-    } else {
-        config.dob
-    };
-
     // Parse mode
-    let mode = Mode::from_str_with_params(
-        &request.mode,
-        dob,
-        Some(request.lifespan.unwrap_or(config.lifespan_years)),
-        Some(request.months.unwrap_or(config.next_months)),
-    )
-    .map_err(|e| e.to_string())?;
+    let mode = Mode::from_str_with_params(&request.mode, &mode_params(&request, &config))
+        .map_err(|e| e.to_string())?;
+    let options = grid_options(&request, &config)?;
+    let survival = survival_curve(&request, &config)?;
 
     // Get theme
     let theme = request
@@ -111,8 +250,18 @@ fn generate_preview(request: GenerateRequest) -> Result<GenerateResponse, String
     let height = request.height.unwrap_or(config.screen_height);
 
     // Calculate grid and render
-    let grid = WeekGrid::calculate(&mode);
-    let image = render_grid(&grid, &theme, width, height);
+    let mut grid = WeekGrid::calculate_with(&mode, &options);
+    let journal = Journal::load().map_err(|e| e.to_string())?;
+    let warnings = grid
+        .apply_overlays(&mode, &config, &journal, survival.as_ref(), &options)
+        .map_err(|e| e.to_string())?;
+    let image = render_grid_with(
+        &grid,
+        &theme,
+        width,
+        height,
+        &render_options(&request, &config)?,
+    );
 
     // Encode as PNG to base64
     let mut buffer = Cursor::new(Vec::new());
@@ -133,6 +282,7 @@ fn generate_preview(request: GenerateRequest) -> Result<GenerateResponse, String
         columns: grid.columns,
         rows: grid.rows,
         image_base64,
+        warnings,
     })
 }
 
@@ -142,21 +292,11 @@ fn set_wallpaper_cmd(request: GenerateRequest) -> Result<String, String> {
     // Load config for defaults
     let config = Config::load().unwrap_or_default();
 
-    // Parse DOB if provided
-    let dob = if let Some(ref dob_str) = request.dob {
-        NaiveDate::parse_from_str(dob_str, "%Y-%m-%d").ok()
-    } else {
-        config.dob
-    };
-
     // Parse mode
-    let mode = Mode::from_str_with_params(
-        &request.mode,
-        dob,
-        Some(request.lifespan.unwrap_or(config.lifespan_years)),
-        Some(request.months.unwrap_or(config.next_months)),
-    )
-    .map_err(|e| e.to_string())?;
+    let mode = Mode::from_str_with_params(&request.mode, &mode_params(&request, &config))
+        .map_err(|e| e.to_string())?;
+    let options = grid_options(&request, &config)?;
+    let survival = survival_curve(&request, &config)?;
 
     // Get theme
     let theme = request
@@ -169,8 +309,17 @@ fn set_wallpaper_cmd(request: GenerateRequest) -> Result<String, String> {
     let height = request.height.unwrap_or(config.screen_height);
 
     // Calculate grid and render
-    let grid = WeekGrid::calculate(&mode);
-    let image = render_grid(&grid, &theme, width, height);
+    let mut grid = WeekGrid::calculate_with(&mode, &options);
+    let journal = Journal::load().map_err(|e| e.to_string())?;
+    grid.apply_overlays(&mode, &config, &journal, survival.as_ref(), &options)
+        .map_err(|e| e.to_string())?;
+    let image = render_grid_with(
+        &grid,
+        &theme,
+        width,
+        height,
+        &render_options(&request, &config)?,
+    );
 
     // Save to output path
     let output_path =
@@ -202,12 +351,44 @@ fn get_config() -> Result<ConfigState, String> {
         screen_height: config.screen_height,
         default_mode: config.default_mode,
         next_months: config.next_months,
+        granularity: config.granularity,
+        week_start: config.week_start,
+        range_start: config.range_start.map(|d| d.format("%Y-%m-%d").to_string()),
+        range_end: config.range_end.map(|d| d.format("%Y-%m-%d").to_string()),
+        range_title: config.range_title,
+        month_rows: config.month_rows,
+        age_rows: config.age_rows,
+        row_labels: config.row_labels,
+        column_labels: config.column_labels,
+        current_week_fill: config.current_week_fill,
+        retirement_age: config.retirement_age,
+        retirement_date: config
+            .retirement_date
+            .map(|d| d.format("%Y-%m-%d").to_string()),
+        vacation_weeks: config.vacation_weeks,
+        public_holidays: config.public_holidays,
+        fiscal_start_month: config.fiscal_start_month,
+        sprint_anchor: config
+            .sprint_anchor
+            .map(|d| d.format("%Y-%m-%d").to_string()),
+        release_date: config
+            .release_date
+            .map(|d| d.format("%Y-%m-%d").to_string()),
+        sprint_weeks: config.sprint_weeks,
+        due_date: config.due_date.map(|d| d.format("%Y-%m-%d").to_string()),
+        birth_date: config.birth_date.map(|d| d.format("%Y-%m-%d").to_string()),
+        sex: config.sex,
+        country: config.country,
+        survival_shading: config.survival_shading,
+        targets: config.targets,
+        people: config.people,
         schedule_installed: is_schedule_installed(),
     })
 }
 
 /// Save configuration
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 fn save_config(
     dob: Option<String>,
     lifespan: Option<u8>,
@@ -216,6 +397,28 @@ fn save_config(
     height: Option<u32>,
     default_mode: Option<String>,
     months: Option<u8>,
+    granularity: Option<String>,
+    range_start: Option<String>,
+    range_end: Option<String>,
+    range_title: Option<String>,
+    month_rows: Option<bool>,
+    age_rows: Option<bool>,
+    labels: Option<bool>,
+    current_week_fill: Option<String>,
+    week_start: Option<String>,
+    sex: Option<String>,
+    country: Option<String>,
+    survival_shading: Option<bool>,
+    due_date: Option<String>,
+    birth_date: Option<String>,
+    fiscal_start_month: Option<u32>,
+    sprint_anchor: Option<String>,
+    release_date: Option<String>,
+    sprint_weeks: Option<u8>,
+    retirement_age: Option<u8>,
+    retirement_date: Option<String>,
+    vacation_weeks: Option<u8>,
+    public_holidays: Option<u8>,
 ) -> Result<String, String> {
     let mut config = Config::load().unwrap_or_default();
 
@@ -251,6 +454,101 @@ fn save_config(
         config.next_months = n;
     }
 
+    if let Some(ref g) = granularity {
+        config.granularity = Granularity::parse(g)?;
+    }
+
+    if let Some(ref start) = range_start {
+        config.range_start = NaiveDate::parse_from_str(start, "%Y-%m-%d").ok();
+    }
+
+    if let Some(ref end) = range_end {
+        config.range_end = NaiveDate::parse_from_str(end, "%Y-%m-%d").ok();
+    }
+
+    if let Some(title) = range_title {
+        config.range_title = Some(title).filter(|t| !t.is_empty());
+    }
+
+    if let Some(rows) = month_rows {
+        config.month_rows = rows;
+    }
+
+    if let Some(rows) = age_rows {
+        config.age_rows = rows;
+    }
+
+    if let Some(enabled) = labels {
+        config.row_labels = enabled;
+        config.column_labels = enabled;
+    }
+
+    if let Some(ref fill) = current_week_fill {
+        config.current_week_fill = CurrentFill::parse(fill)?;
+    }
+
+    if let Some(ref w) = week_start {
+        config.week_start = WeekStart::parse(w)?;
+    }
+
+    if let Some(ref s) = sex {
+        config.sex = Some(Sex::parse(s)?);
+    }
+
+    if let Some(c) = country {
+        config.country = Some(c);
+    }
+
+    if let Some(enabled) = survival_shading {
+        config.survival_shading = enabled;
+    }
+
+    if let Some(age) = retirement_age {
+        config.retirement_age = age;
+    }
+
+    if let Some(ref date) = retirement_date {
+        config.retirement_date = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok();
+    }
+
+    if let Some(weeks) = vacation_weeks {
+        config
+            .set("vacation_weeks", &weeks.to_string())
+            .map_err(|e| e.to_string())?;
+    }
+
+    if let Some(days) = public_holidays {
+        config.public_holidays = days;
+    }
+
+    if let Some(month) = fiscal_start_month {
+        config
+            .set("fiscal_start_month", &month.to_string())
+            .map_err(|e| e.to_string())?;
+    }
+
+    if let Some(ref anchor) = sprint_anchor {
+        config.sprint_anchor = NaiveDate::parse_from_str(anchor, "%Y-%m-%d").ok();
+    }
+
+    if let Some(ref release) = release_date {
+        config.release_date = NaiveDate::parse_from_str(release, "%Y-%m-%d").ok();
+    }
+
+    if let Some(weeks) = sprint_weeks {
+        config
+            .set("sprint_weeks", &weeks.to_string())
+            .map_err(|e| e.to_string())?;
+    }
+
+    if let Some(ref due) = due_date {
+        config.due_date = NaiveDate::parse_from_str(due, "%Y-%m-%d").ok();
+    }
+
+    if let Some(ref born) = birth_date {
+        config.birth_date = NaiveDate::parse_from_str(born, "%Y-%m-%d").ok();
+    }
+
     config
         .save()
         .map_err(|e| format!("Failed to save config: {}", e))?;
@@ -258,15 +556,107 @@ fn save_config(
     Ok("Configuration saved".to_string())
 }
 
+/// Estimate lifespan from sex, country and date of birth
+#[tauri::command]
+fn estimate_lifespan(
+    sex: String,
+    country: String,
+    dob: String,
+) -> Result<LifespanResponse, String> {
+    let dob = NaiveDate::parse_from_str(&dob, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date: {}. Use YYYY-MM-DD", dob))?;
+    let today = chrono::Local::now().date_naive();
+    let estimate = liw_core::estimate_lifespan(Sex::parse(&sex)?, &country, dob, today)?;
+
+    Ok(LifespanResponse {
+        lifespan_years: estimate.lifespan_years.round().min(u8::MAX as f64) as u8,
+        remaining_years: estimate.remaining_years,
+    })
+}
+
+/// Add a countdown target
+#[tauri::command]
+fn add_target(name: String, date: String, color: Option<String>) -> Result<String, String> {
+    let mut config = Config::load().unwrap_or_default();
+
+    let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date: {}. Use YYYY-MM-DD", date))?;
+    config
+        .add_target(CountdownTarget {
+            name: name.clone(),
+            date,
+            color: color.filter(|c| !c.is_empty()),
+        })
+        .map_err(|e| e.to_string())?;
+    config
+        .save()
+        .map_err(|e| format!("Failed to save config: {}", e))?;
+
+    Ok(format!("Target added: {}", name))
+}
+
+/// Remove a countdown target by name
+#[tauri::command]
+fn remove_target(name: String) -> Result<String, String> {
+    let mut config = Config::load().unwrap_or_default();
+
+    let target = config.remove_target(&name).map_err(|e| e.to_string())?;
+    config
+        .save()
+        .map_err(|e| format!("Failed to save config: {}", e))?;
+
+    Ok(format!("Target removed: {}", target.name))
+}
+
+/// Add a person to family mode
+#[tauri::command]
+fn add_person(
+    name: String,
+    dob: String,
+    lifespan: Option<u8>,
+    color: Option<String>,
+) -> Result<String, String> {
+    let mut config = Config::load().unwrap_or_default();
+
+    let dob = NaiveDate::parse_from_str(&dob, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date: {}. Use YYYY-MM-DD", dob))?;
+    config
+        .add_person(Person {
+            name: name.clone(),
+            dob,
+            lifespan_years: lifespan,
+            color: color.filter(|c| !c.is_empty()),
+        })
+        .map_err(|e| e.to_string())?;
+    config
+        .save()
+        .map_err(|e| format!("Failed to save config: {}", e))?;
+
+    Ok(format!("Person added: {}", name))
+}
+
+/// Remove a person from family mode by name
+#[tauri::command]
+fn remove_person(name: String) -> Result<String, String> {
+    let mut config = Config::load().unwrap_or_default();
+
+    let person = config.remove_person(&name).map_err(|e| e.to_string())?;
+    config
+        .save()
+        .map_err(|e| format!("Failed to save config: {}", e))?;
+
+    Ok(format!("Person removed: {}", person.name))
+}
+
 /// Toggle automatic schedule
 #[tauri::command]
 fn toggle_schedule(enabled: bool) -> Result<String, String> {
     if enabled {
         install_schedule().map_err(|e| format!("Failed to install schedule: {}", e))?;
-        Ok("Weekly schedule installed".to_string())
+        Ok("Daily schedule installed".to_string())
     } else {
         uninstall_schedule().map_err(|e| format!("Failed to uninstall schedule: {}", e))?;
-        Ok("Weekly schedule removed".to_string())
+        Ok("Daily schedule removed".to_string())
     }
 }
 
@@ -284,6 +674,11 @@ fn main() {
             set_wallpaper_cmd,
             get_config,
             save_config,
+            add_target,
+            remove_target,
+            add_person,
+            remove_person,
+            estimate_lifespan,
             toggle_schedule,
             get_schedule_status,
         ])
@@ -8,6 +8,8 @@
 let currentMode = 'year-end';
 let currentTheme = 'dark';
 let hasPreview = false;
+let targets = [];
+let people = [];
 
 // DOM Elements
 const elements = {
@@ -16,6 +18,38 @@ const elements = {
     dobInput: document.getElementById('dob'),
     lifespanInput: document.getElementById('lifespan'),
     monthsInput: document.getElementById('months'),
+    granularityInput: document.getElementById('granularity'),
+    weekStartInput: document.getElementById('week-start'),
+    asOfInput: document.getElementById('as-of'),
+    monthRowsInput: document.getElementById('month-rows'),
+    ageRowsInput: document.getElementById('age-rows'),
+    labelsInput: document.getElementById('labels'),
+    currentFillInput: document.getElementById('current-fill'),
+    rangeStartInput: document.getElementById('range-start'),
+    rangeEndInput: document.getElementById('range-end'),
+    rangeTitleInput: document.getElementById('range-title'),
+    retirementAgeInput: document.getElementById('retirement-age'),
+    retirementDateInput: document.getElementById('retirement-date'),
+    vacationWeeksInput: document.getElementById('vacation-weeks'),
+    publicHolidaysInput: document.getElementById('public-holidays'),
+    fiscalStartInput: document.getElementById('fiscal-start'),
+    sprintAnchorInput: document.getElementById('sprint-anchor'),
+    releaseDateInput: document.getElementById('release-date'),
+    sprintWeeksInput: document.getElementById('sprint-weeks'),
+    dueDateInput: document.getElementById('due-date'),
+    birthDateInput: document.getElementById('birth-date'),
+    targetList: document.getElementById('target-list'),
+    targetNameInput: document.getElementById('target-name'),
+    targetDateInput: document.getElementById('target-date'),
+    addTargetBtn: document.getElementById('add-target'),
+    personList: document.getElementById('person-list'),
+    personNameInput: document.getElementById('person-name'),
+    personDobInput: document.getElementById('person-dob'),
+    addPersonBtn: document.getElementById('add-person'),
+    estimateLifespanBtn: document.getElementById('estimate-lifespan'),
+    sexInput: document.getElementById('sex'),
+    countryInput: document.getElementById('country'),
+    survivalInput: document.getElementById('survival'),
     widthInput: document.getElementById('width'),
     heightInput: document.getElementById('height'),
     detectResolutionBtn: document.getElementById('detect-resolution'),
@@ -33,6 +67,14 @@ const elements = {
     toastMessage: document.getElementById('toast-message'),
     lifeOnlyElements: document.querySelectorAll('.life-only'),
     monthsOnlyElements: document.querySelectorAll('.months-only'),
+    rangeOnlyElements: document.querySelectorAll('.range-only'),
+    countdownOnlyElements: document.querySelectorAll('.countdown-only'),
+    fullYearOnlyElements: document.querySelectorAll('.full-year-only'),
+    familyOnlyElements: document.querySelectorAll('.family-only'),
+    retirementOnlyElements: document.querySelectorAll('.retirement-only'),
+    fiscalOnlyElements: document.querySelectorAll('.fiscal-only'),
+    sprintOnlyElements: document.querySelectorAll('.sprint-only'),
+    pregnancyOnlyElements: document.querySelectorAll('.pregnancy-only'),
 };
 
 // Tauri API (available via withGlobalTauri)
@@ -71,6 +113,13 @@ function setupEventListeners() {
         });
     });
 
+    // Add countdown target button
+    elements.addTargetBtn.addEventListener('click', addTarget);
+
+    // Add family member button
+    elements.addPersonBtn.addEventListener('click', addPerson);
+    elements.estimateLifespanBtn.addEventListener('click', estimateLifespan);
+
     // Detect resolution button
     elements.detectResolutionBtn.addEventListener('click', detectScreenResolution);
 
@@ -88,6 +137,21 @@ function setupEventListeners() {
         elements.dobInput,
         elements.lifespanInput,
         elements.monthsInput,
+        elements.granularityInput,
+        elements.weekStartInput,
+        elements.asOfInput,
+        elements.monthRowsInput,
+        elements.ageRowsInput,
+        elements.labelsInput,
+        elements.currentFillInput,
+        elements.survivalInput,
+        elements.sexInput,
+        elements.countryInput,
+        elements.rangeStartInput,
+        elements.rangeEndInput,
+        elements.rangeTitleInput,
+        elements.dueDateInput,
+        elements.birthDateInput,
     ];
     inputs.forEach(input => {
         input.addEventListener('change', () => {
@@ -102,6 +166,14 @@ function setupEventListeners() {
 function updateModeVisibility() {
     const isLifeMode = currentMode === 'life';
     const isMonthsMode = currentMode === 'next-months';
+    const isRangeMode = currentMode === 'range';
+    const isCountdownMode = currentMode === 'countdown';
+    const isFullYearMode = currentMode === 'full-year';
+    const isFamilyMode = currentMode === 'family';
+    const isRetirementMode = currentMode === 'retirement';
+    const isFiscalMode = currentMode === 'fiscal';
+    const isSprintMode = currentMode === 'sprint';
+    const isPregnancyMode = currentMode === 'pregnancy';
 
     elements.lifeOnlyElements.forEach(el => {
         el.classList.toggle('hidden', !isLifeMode);
@@ -110,6 +182,38 @@ function updateModeVisibility() {
     elements.monthsOnlyElements.forEach(el => {
         el.classList.toggle('hidden', !isMonthsMode);
     });
+
+    elements.rangeOnlyElements.forEach(el => {
+        el.classList.toggle('hidden', !isRangeMode);
+    });
+
+    elements.countdownOnlyElements.forEach(el => {
+        el.classList.toggle('hidden', !isCountdownMode);
+    });
+
+    elements.fullYearOnlyElements.forEach(el => {
+        el.classList.toggle('hidden', !isFullYearMode);
+    });
+
+    elements.familyOnlyElements.forEach(el => {
+        el.classList.toggle('hidden', !isFamilyMode);
+    });
+
+    elements.retirementOnlyElements.forEach(el => {
+        el.classList.toggle('hidden', !isRetirementMode);
+    });
+
+    elements.fiscalOnlyElements.forEach(el => {
+        el.classList.toggle('hidden', !isFiscalMode);
+    });
+
+    elements.sprintOnlyElements.forEach(el => {
+        el.classList.toggle('hidden', !isSprintMode);
+    });
+
+    elements.pregnancyOnlyElements.forEach(el => {
+        el.classList.toggle('hidden', !isPregnancyMode);
+    });
 }
 
 /**
@@ -126,6 +230,32 @@ async function loadConfig() {
         elements.widthInput.value = config.screen_width;
         elements.heightInput.value = config.screen_height;
         elements.monthsInput.value = config.next_months;
+        elements.granularityInput.value = config.granularity;
+        elements.weekStartInput.value = config.week_start;
+        elements.monthRowsInput.checked = config.month_rows;
+        elements.ageRowsInput.checked = config.age_rows;
+        elements.labelsInput.checked = config.row_labels || config.column_labels;
+        elements.currentFillInput.value = config.current_week_fill;
+        elements.survivalInput.checked = config.survival_shading;
+        if (config.sex) elements.sexInput.value = config.sex;
+        if (config.country) elements.countryInput.value = config.country;
+        elements.rangeStartInput.value = config.range_start || '';
+        elements.rangeEndInput.value = config.range_end || '';
+        elements.rangeTitleInput.value = config.range_title || '';
+        elements.retirementAgeInput.value = config.retirement_age;
+        elements.retirementDateInput.value = config.retirement_date || '';
+        elements.vacationWeeksInput.value = config.vacation_weeks;
+        elements.publicHolidaysInput.value = config.public_holidays;
+        elements.fiscalStartInput.value = config.fiscal_start_month;
+        elements.sprintAnchorInput.value = config.sprint_anchor || '';
+        elements.releaseDateInput.value = config.release_date || '';
+        elements.sprintWeeksInput.value = config.sprint_weeks;
+        elements.dueDateInput.value = config.due_date || '';
+        elements.birthDateInput.value = config.birth_date || '';
+        targets = config.targets || [];
+        people = config.people || [];
+        renderTargets();
+        renderPeople();
         elements.scheduleToggle.checked = config.schedule_installed;
 
         // Set theme
@@ -147,6 +277,163 @@ async function loadConfig() {
     }
 }
 
+/**
+ * Render the list of countdown targets
+ */
+function renderTargets() {
+    elements.targetList.innerHTML = '';
+    targets.forEach(target => {
+        const item = document.createElement('li');
+        item.className = 'target-item';
+
+        const label = document.createElement('span');
+        label.textContent = `${target.date}  ${target.name}`;
+
+        const remove = document.createElement('button');
+        remove.className = 'target-remove';
+        remove.title = 'Remove target';
+        remove.textContent = '×';
+        remove.addEventListener('click', () => removeTarget(target.name));
+
+        item.append(label, remove);
+        elements.targetList.appendChild(item);
+    });
+}
+
+/**
+ * Add a countdown target from the input fields
+ */
+async function addTarget() {
+    const name = elements.targetNameInput.value.trim();
+    const date = elements.targetDateInput.value;
+    if (!name || !date) {
+        showToast('Enter a target name and date', 'error');
+        return;
+    }
+
+    try {
+        const message = await invoke('add_target', { name, date, color: null });
+        const config = await invoke('get_config');
+        targets = config.targets;
+        renderTargets();
+        elements.targetNameInput.value = '';
+        showToast(message, 'success');
+        if (hasPreview) generatePreview();
+    } catch (error) {
+        showToast(`Error: ${error}`, 'error');
+        console.error('Add target error:', error);
+    }
+}
+
+/**
+ * Fill the lifespan input from the embedded life tables
+ */
+async function estimateLifespan() {
+    const dob = elements.dobInput.value;
+    if (!dob) {
+        showToast('Enter your date of birth first', 'error');
+        return;
+    }
+
+    try {
+        const estimate = await invoke('estimate_lifespan', {
+            sex: elements.sexInput.value,
+            country: elements.countryInput.value,
+            dob,
+        });
+        elements.lifespanInput.value = estimate.lifespan_years;
+        showToast(
+            `Estimated lifespan: ${estimate.lifespan_years} years (${estimate.remaining_years.toFixed(1)} remaining)`,
+            'success'
+        );
+        await saveConfig();
+        if (hasPreview) generatePreview();
+    } catch (error) {
+        showToast(`Error: ${error}`, 'error');
+        console.error('Estimate lifespan error:', error);
+    }
+}
+
+/**
+ * Remove a countdown target by name
+ */
+async function removeTarget(name) {
+    try {
+        const message = await invoke('remove_target', { name });
+        targets = targets.filter(t => t.name !== name);
+        renderTargets();
+        showToast(message, 'success');
+        if (hasPreview) generatePreview();
+    } catch (error) {
+        showToast(`Error: ${error}`, 'error');
+        console.error('Remove target error:', error);
+    }
+}
+
+/**
+ * Render the list of family members
+ */
+function renderPeople() {
+    elements.personList.innerHTML = '';
+    people.forEach(person => {
+        const item = document.createElement('li');
+        item.className = 'target-item';
+
+        const label = document.createElement('span');
+        label.textContent = `${person.dob}  ${person.name}`;
+
+        const remove = document.createElement('button');
+        remove.className = 'target-remove';
+        remove.title = 'Remove person';
+        remove.textContent = '×';
+        remove.addEventListener('click', () => removePerson(person.name));
+
+        item.append(label, remove);
+        elements.personList.appendChild(item);
+    });
+}
+
+/**
+ * Add a family member from the input fields
+ */
+async function addPerson() {
+    const name = elements.personNameInput.value.trim();
+    const dob = elements.personDobInput.value;
+    if (!name || !dob) {
+        showToast('Enter a name and date of birth', 'error');
+        return;
+    }
+
+    try {
+        const message = await invoke('add_person', { name, dob, lifespan: null, color: null });
+        const config = await invoke('get_config');
+        people = config.people;
+        renderPeople();
+        elements.personNameInput.value = '';
+        showToast(message, 'success');
+        if (hasPreview) generatePreview();
+    } catch (error) {
+        showToast(`Error: ${error}`, 'error');
+        console.error('Add person error:', error);
+    }
+}
+
+/**
+ * Remove a family member by name
+ */
+async function removePerson(name) {
+    try {
+        const message = await invoke('remove_person', { name });
+        people = people.filter(p => p.name !== name);
+        renderPeople();
+        showToast(message, 'success');
+        if (hasPreview) generatePreview();
+    } catch (error) {
+        showToast(`Error: ${error}`, 'error');
+        console.error('Remove person error:', error);
+    }
+}
+
 /**
  * Detect screen resolution
  */
@@ -192,7 +479,12 @@ async function generatePreview() {
         elements.setWallpaperBtn.disabled = false;
         hasPreview = true;
 
-        showToast('Preview generated!', 'success');
+        if (response.warnings.length > 0) {
+            response.warnings.forEach((warning) => console.warn(warning));
+            showToast(`Preview generated, ${response.warnings.length} calendar event(s) skipped`, 'info');
+        } else {
+            showToast('Preview generated!', 'success');
+        }
     } catch (error) {
         showToast(`Error: ${error}`, 'error');
         console.error('Generate preview error:', error);
@@ -256,6 +548,28 @@ async function saveConfig() {
             height: parseInt(elements.heightInput.value) || null,
             defaultMode: currentMode,
             months: parseInt(elements.monthsInput.value) || null,
+            granularity: elements.granularityInput.value,
+            rangeStart: elements.rangeStartInput.value || null,
+            rangeEnd: elements.rangeEndInput.value || null,
+            rangeTitle: elements.rangeTitleInput.value,
+            monthRows: elements.monthRowsInput.checked,
+            ageRows: elements.ageRowsInput.checked,
+            labels: elements.labelsInput.checked,
+            currentWeekFill: elements.currentFillInput.value,
+            weekStart: elements.weekStartInput.value,
+            sex: elements.sexInput.value,
+            country: elements.countryInput.value,
+            survivalShading: elements.survivalInput.checked,
+            dueDate: elements.dueDateInput.value,
+            birthDate: elements.birthDateInput.value,
+            fiscalStartMonth: parseInt(elements.fiscalStartInput.value),
+            sprintAnchor: elements.sprintAnchorInput.value || null,
+            releaseDate: elements.releaseDateInput.value || null,
+            sprintWeeks: parseInt(elements.sprintWeeksInput.value) || null,
+            retirementAge: parseInt(elements.retirementAgeInput.value) || null,
+            retirementDate: elements.retirementDateInput.value,
+            vacationWeeks: parseInt(elements.vacationWeeksInput.value) || 0,
+            publicHolidays: parseInt(elements.publicHolidaysInput.value) || 0,
         });
     } catch (error) {
         console.error('Save config error:', error);
@@ -271,6 +585,29 @@ function buildRequest() {
         dob: elements.dobInput.value || null,
         lifespan: parseInt(elements.lifespanInput.value) || null,
         months: parseInt(elements.monthsInput.value) || null,
+        start: elements.rangeStartInput.value || null,
+        end: elements.rangeEndInput.value || null,
+        title: elements.rangeTitleInput.value || null,
+        month_rows: elements.monthRowsInput.checked,
+        age_rows: elements.ageRowsInput.checked,
+        retirement_age: parseInt(elements.retirementAgeInput.value) || null,
+        retirement_date: elements.retirementDateInput.value || null,
+        vacation_weeks: parseInt(elements.vacationWeeksInput.value) || 0,
+        public_holidays: parseInt(elements.publicHolidaysInput.value) || 0,
+        fiscal_start_month: parseInt(elements.fiscalStartInput.value),
+        sprint_anchor: elements.sprintAnchorInput.value || null,
+        release: elements.releaseDateInput.value || null,
+        sprint_weeks: parseInt(elements.sprintWeeksInput.value) || null,
+        due: elements.dueDateInput.value || null,
+        born: elements.birthDateInput.value || null,
+        survival: elements.survivalInput.checked,
+        labels: elements.labelsInput.checked,
+        current_fill: elements.currentFillInput.value,
+        sex: elements.sexInput.value,
+        country: elements.countryInput.value,
+        granularity: elements.granularityInput.value,
+        week_start: elements.weekStartInput.value,
+        as_of: elements.asOfInput.value || null,
         theme: currentTheme,
         width: parseInt(elements.widthInput.value) || null,
         height: parseInt(elements.heightInput.value) || null,